@@ -8,12 +8,9 @@ pub mod factory {
         ToAccountId,
     };
     use ink_storage::traits::SpreadAllocate;
-    use openbrush::{
-        contracts::ownable::*,
-        traits::{Storage, ZERO_ADDRESS},
-    };
+    use openbrush::traits::{Storage, ZERO_ADDRESS};
     use pool_contract::pool::PoolContractRef;
-    use uniswap_v3::{impls::factory::*, traits::factory::*};
+    use uniswap_v3::{helpers::helper::DEFAULT_FEE_AMOUNTS, impls::factory::*, traits::factory::*};
 
     #[ink(event)]
     pub struct PoolCreated {
@@ -47,8 +44,6 @@ pub mod factory {
     pub struct FactoryContract {
         #[storage_field]
         factory: data::Data,
-        #[storage_field]
-        ownable: ownable::Data,
     }
 
     impl Factory for FactoryContract {
@@ -89,28 +84,261 @@ pub mod factory {
             )
         }
 
-        fn _instantiate_pool(&mut self, salt_bytes: &[u8]) -> Result<AccountId, FactoryError> {
+        fn _instantiate_pool(
+            &mut self,
+            salt_bytes: &[u8],
+            token_0: AccountId,
+            token_1: AccountId,
+            fee: u32,
+            tick_spacing: i32,
+        ) -> Result<AccountId, FactoryError> {
             let pool_hash = self.factory.pool_contract_code_hash;
-            let pool = PoolContractRef::new()
+            let pool = PoolContractRef::new(token_0, token_1, fee, tick_spacing)
                 .endowment(0)
                 .code_hash(pool_hash)
-                .salt_bytes(&salt_bytes[..4])
+                .salt_bytes(salt_bytes)
                 .instantiate()
                 .map_err(|_| FactoryError::PoolInstantiationFailed)?;
             Ok(pool.to_account_id())
         }
     }
 
-    impl Ownable for FactoryContract {}
-
     impl FactoryContract {
         #[ink(constructor)]
         pub fn new(pool_code_hash: Hash) -> Self {
             ink_lang::codegen::initialize_contract(|instance: &mut Self| {
                 instance.factory.pool_contract_code_hash = pool_code_hash;
+                instance.factory.version = data::STORAGE_VERSION;
                 let caller = instance.env().caller();
-                instance._init_with_owner(caller);
+                instance.factory.owner = caller;
+                instance._emit_owner_changed_event(ZERO_ADDRESS.into(), caller);
+                for (fee, tick_spacing) in DEFAULT_FEE_AMOUNTS {
+                    instance._enable_fee_amount(fee, tick_spacing);
+                }
             })
         }
     }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use ink_env::{test, DefaultEnvironment};
+        use uniswap_v3::helpers::swap_math::FEE_DENOMINATOR;
+
+        fn accounts() -> test::DefaultAccounts<DefaultEnvironment> {
+            test::default_accounts::<DefaultEnvironment>()
+        }
+
+        // deployed by alice, the default caller
+        fn deploy() -> FactoryContract {
+            FactoryContract::new(Hash::default())
+        }
+
+        #[ink_lang::test]
+        fn constructor_enables_the_default_fee_amounts() {
+            let factory = deploy();
+            assert_eq!(factory.get_fee_amount_tick_spacing(500), Some(10));
+            assert_eq!(factory.get_fee_amount_tick_spacing(3000), Some(60));
+            assert_eq!(factory.get_fee_amount_tick_spacing(10000), Some(200));
+            assert_eq!(factory.get_fee_amount_tick_spacing(100), None);
+        }
+
+        #[ink_lang::test]
+        fn enable_fee_amount_accepts_fees_below_the_denominator() {
+            let mut factory = deploy();
+            assert_eq!(factory.enable_fee_amount(100, 1), Ok(()));
+            assert_eq!(factory.get_fee_amount_tick_spacing(100), Some(1));
+            assert_eq!(
+                factory.enable_fee_amount(FEE_DENOMINATOR - 1, 16383),
+                Ok(())
+            );
+            assert_eq!(
+                factory.enable_fee_amount(FEE_DENOMINATOR, 1),
+                Err(FactoryError::FeeTooBig)
+            );
+        }
+
+        #[ink_lang::test]
+        fn enable_fee_amount_validates_tick_spacing_and_owner() {
+            let mut factory = deploy();
+            assert_eq!(
+                factory.enable_fee_amount(100, 0),
+                Err(FactoryError::TickSpacingOutOfBonds)
+            );
+            assert_eq!(
+                factory.enable_fee_amount(100, 16384),
+                Err(FactoryError::TickSpacingOutOfBonds)
+            );
+            assert_eq!(
+                factory.enable_fee_amount(500, 1),
+                Err(FactoryError::NonZeroTickSpacing)
+            );
+            test::set_caller::<DefaultEnvironment>(accounts().bob);
+            assert_eq!(
+                factory.enable_fee_amount(100, 1),
+                Err(FactoryError::CallerIsNotOwner)
+            );
+        }
+
+        #[ink_lang::test]
+        fn register_pool_tracks_every_pool() {
+            let mut factory = deploy();
+            let (token_0, token_1) = (AccountId::from([1; 32]), AccountId::from([2; 32]));
+            let pool = AccountId::from([10; 32]);
+            assert!(!factory.is_pool(pool));
+            assert_eq!(factory.all_pools_length(), 0);
+
+            factory._register_pool(token_0, token_1, pool);
+            assert!(factory.is_pool(pool));
+            assert!(!factory.is_pool(token_0));
+            assert_eq!(factory.all_pools_length(), 1);
+            assert_eq!(factory.all_pools(0), Some(pool));
+            assert_eq!(factory.all_pools(1), None);
+        }
+
+        #[ink_lang::test]
+        fn pools_for_token_pages_in_creation_order() {
+            let mut factory = deploy();
+            let token = AccountId::from([1; 32]);
+            let pools = [
+                AccountId::from([10; 32]),
+                AccountId::from([11; 32]),
+                AccountId::from([12; 32]),
+            ];
+            for (i, pool) in pools.iter().enumerate() {
+                factory._register_pool(token, AccountId::from([2 + i as u8; 32]), *pool);
+            }
+            assert_eq!(factory.pools_for_token_length(token), 3);
+            assert_eq!(factory.pools_for_token(token, 0, 2), pools[..2].to_vec());
+            assert_eq!(factory.pools_for_token(token, 2, 2), pools[2..].to_vec());
+            assert_eq!(
+                factory.pools_for_token(token, 1, u64::MAX),
+                pools[1..].to_vec()
+            );
+            assert_eq!(factory.pools_for_token(token, 3, 1), Vec::new());
+            assert_eq!(factory.pools_for_token(token, 0, 0), Vec::new());
+            assert_eq!(factory.pools_for_token_length(AccountId::from([2; 32])), 1);
+        }
+
+        #[ink_lang::test]
+        fn pools_for_pair_pages_in_either_token_order() {
+            let mut factory = deploy();
+            let (token_0, token_1) = (AccountId::from([1; 32]), AccountId::from([2; 32]));
+            let pools = [AccountId::from([10; 32]), AccountId::from([11; 32])];
+            for pool in pools {
+                factory._register_pool(token_0, token_1, pool);
+            }
+            factory._register_pool(token_0, AccountId::from([3; 32]), AccountId::from([12; 32]));
+
+            assert_eq!(factory.pools_for_pair_length(token_0, token_1), 2);
+            assert_eq!(factory.pools_for_pair_length(token_1, token_0), 2);
+            assert_eq!(
+                factory.pools_for_pair(token_1, token_0, 0, 10),
+                pools.to_vec()
+            );
+            assert_eq!(
+                factory.pools_for_pair(token_0, token_1, 1, 1),
+                pools[1..].to_vec()
+            );
+            assert_eq!(factory.pools_for_pair(token_0, token_1, 2, 1), Vec::new());
+        }
+
+        #[ink_lang::test]
+        fn only_the_proposed_owner_can_accept() {
+            let accounts = accounts();
+            let mut factory = deploy();
+            assert_eq!(
+                factory.accept_owner(),
+                Err(FactoryError::CallerIsNotPendingOwner)
+            );
+
+            assert_eq!(factory.propose_owner(accounts.bob), Ok(()));
+            assert_eq!(factory.pending_owner(), Some(accounts.bob));
+            for caller in [accounts.alice, accounts.charlie] {
+                test::set_caller::<DefaultEnvironment>(caller);
+                assert_eq!(
+                    factory.accept_owner(),
+                    Err(FactoryError::CallerIsNotPendingOwner)
+                );
+            }
+            assert_eq!(factory.owner(), accounts.alice);
+
+            test::set_caller::<DefaultEnvironment>(accounts.bob);
+            assert_eq!(
+                factory.propose_owner(accounts.bob),
+                Err(FactoryError::CallerIsNotOwner)
+            );
+            assert_eq!(factory.accept_owner(), Ok(()));
+            assert_eq!(factory.owner(), accounts.bob);
+            assert_eq!(factory.pending_owner(), None);
+        }
+
+        #[ink_lang::test]
+        fn previous_owner_loses_its_rights_after_acceptance() {
+            let accounts = accounts();
+            let mut factory = deploy();
+            assert_eq!(factory.propose_owner(accounts.bob), Ok(()));
+            test::set_caller::<DefaultEnvironment>(accounts.bob);
+            assert_eq!(factory.accept_owner(), Ok(()));
+
+            test::set_caller::<DefaultEnvironment>(accounts.alice);
+            assert_eq!(
+                factory.propose_owner(accounts.alice),
+                Err(FactoryError::CallerIsNotOwner)
+            );
+            assert_eq!(
+                factory.enable_fee_amount(100, 1),
+                Err(FactoryError::CallerIsNotOwner)
+            );
+            assert_eq!(
+                factory.set_pool_code_hash([1; 32]),
+                Err(FactoryError::CallerIsNotOwner)
+            );
+
+            test::set_caller::<DefaultEnvironment>(accounts.bob);
+            assert_eq!(factory.enable_fee_amount(100, 1), Ok(()));
+        }
+
+        #[ink_lang::test]
+        fn accept_owner_emits_owner_changed() {
+            let accounts = accounts();
+            let mut factory = deploy();
+            assert_eq!(factory.propose_owner(accounts.bob), Ok(()));
+            test::set_caller::<DefaultEnvironment>(accounts.bob);
+            assert_eq!(factory.accept_owner(), Ok(()));
+
+            let event = test::recorded_events().last().unwrap();
+            match <Event as scale::Decode>::decode(&mut &event.data[..]).unwrap() {
+                Event::OwnerChanged(OwnerChanged {
+                    original_owner,
+                    new_owner,
+                }) => {
+                    assert_eq!(original_owner, accounts.alice);
+                    assert_eq!(new_owner, accounts.bob);
+                }
+                _ => panic!("expected OwnerChanged"),
+            }
+        }
+
+        #[ink_lang::test]
+        fn migrate_rejects_up_to_date_storage() {
+            let mut factory = deploy();
+            assert_eq!(factory.storage_version(), data::STORAGE_VERSION);
+            assert_eq!(factory.migrate(), Err(FactoryError::StorageUpToDate));
+        }
+
+        #[ink_lang::test]
+        fn migrate_bumps_the_storage_version() {
+            let mut factory = deploy();
+            factory.factory.version = data::STORAGE_VERSION - 1;
+
+            test::set_caller::<DefaultEnvironment>(accounts().bob);
+            assert_eq!(factory.migrate(), Err(FactoryError::CallerIsNotOwner));
+
+            test::set_caller::<DefaultEnvironment>(accounts().alice);
+            assert_eq!(factory.migrate(), Ok(()));
+            assert_eq!(factory.storage_version(), data::STORAGE_VERSION);
+            assert_eq!(factory.migrate(), Err(FactoryError::StorageUpToDate));
+        }
+    }
 }
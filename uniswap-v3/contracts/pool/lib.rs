@@ -15,11 +15,14 @@ pub mod pool {
         },
         traits::*,
     };
-    use uniswap_v3::{impls::pool::*, traits::pool::*};
+    use primitive_types::U256;
+    use uniswap_v3::{
+        helpers::tick::tick_spacing_to_max_liquidity_per_tick, impls::pool::*, traits::pool::*,
+    };
     #[ink(event)]
     pub struct Initialize {
         #[ink(topic)]
-        sqrt_price_x96: u128,
+        sqrt_price_x96: U256,
         #[ink(topic)]
         tick: i32,
     }
@@ -30,24 +33,39 @@ pub mod pool {
         tick_upper: i32,
         #[ink(topic)]
         amount: Balance,
+        amount_0: Balance,
+        amount_1: Balance,
+    }
+
+    #[ink(event)]
+    pub struct Burn {
+        owner: AccountId,
+        tick_lower: i32,
+        tick_upper: i32,
+        #[ink(topic)]
+        amount: Balance,
+        amount_0: Balance,
+        amount_1: Balance,
     }
 
     #[ink(event)]
     pub struct Collect {
+        #[ink(topic)]
+        owner: AccountId,
         #[ink(topic)]
         recipient: AccountId,
         tick_lower: i32,
         tick_upper: i32,
-        amount0_requested: Balance,
-        amount1_requested: Balance,
+        amount0: Balance,
+        amount1: Balance,
     }
     #[ink(event)]
     pub struct Swap {
         sender: AccountId,
         recipient: AccountId,
-        amount0: Balance,
-        amount1: Balance,
-        sqrt_price_x96: u128,
+        amount0: i128,
+        amount1: i128,
+        sqrt_price_x96: U256,
         liquidity: u128,
         tick: i32,
     }
@@ -74,11 +92,12 @@ pub mod pool {
     }
     #[ink(event)]
     pub struct CollectProtocol {
+        #[ink(topic)]
         sender: AccountId,
         #[ink(topic)]
         recipient: AccountId,
-        amount0_requested: Balance,
-        amount1_requested: Balance,
+        amount0: Balance,
+        amount1: Balance,
     }
 
     #[ink(event)]
@@ -134,7 +153,7 @@ pub mod pool {
     }
 
     impl pool::Internal for PoolContract {
-        fn _emit_initialize_event(&self, sqrt_price_x96: u128, tick: i32) {
+        fn _emit_initialize_event(&self, sqrt_price_x96: U256, tick: i32) {
             self.env().emit_event(Initialize {
                 sqrt_price_x96,
                 tick,
@@ -143,18 +162,20 @@ pub mod pool {
 
         fn _emit_collect_event(
             &self,
+            owner: AccountId,
             recipient: AccountId,
             tick_lower: i32,
             tick_upper: i32,
-            amount0_requested: Balance,
-            amount1_requested: Balance,
+            amount0: Balance,
+            amount1: Balance,
         ) {
             self.env().emit_event(Collect {
+                owner,
                 recipient,
                 tick_lower,
                 tick_upper,
-                amount0_requested,
-                amount1_requested,
+                amount0,
+                amount1,
             });
         }
 
@@ -162,9 +183,9 @@ pub mod pool {
             &self,
             sender: AccountId,
             recipient: AccountId,
-            amount0: Balance,
-            amount1: Balance,
-            sqrt_price_x96: u128,
+            amount0: i128,
+            amount1: i128,
+            sqrt_price_x96: U256,
             liquidity: u128,
             tick: i32,
         ) {
@@ -214,18 +235,66 @@ pub mod pool {
             &self,
             sender: AccountId,
             recipient: AccountId,
-            amount0_requested: Balance,
-            amount1_requested: Balance,
+            amount0: Balance,
+            amount1: Balance,
         ) {
             self.env().emit_event(CollectProtocol {
                 sender,
                 recipient,
-                amount0_requested,
-                amount1_requested,
+                amount0,
+                amount1,
+            });
+        }
+        fn _emit_increase_observation_cardinality_next_event(
+            &self,
+            observation_cardinality_next_old: u16,
+            observation_cardinality_next_new: u16,
+        ) {
+            self.env().emit_event(IncreaseObservationCardinalityNext {
+                observation_cardinality_next_old,
+                observation_cardinality_next_new,
             });
         }
     }
     impl Pool for PoolContract {
+        fn _emit_mint_event(
+            &self,
+            recipient: AccountId,
+            tick_lower: i32,
+            tick_upper: i32,
+            amount: Balance,
+            amount_0: Balance,
+            amount_1: Balance,
+        ) {
+            self.env().emit_event(Mint {
+                recipient,
+                tick_lower,
+                tick_upper,
+                amount,
+                amount_0,
+                amount_1,
+            });
+        }
+
+        fn _emit_burn_event(
+            &self,
+            owner: AccountId,
+            tick_lower: i32,
+            tick_upper: i32,
+            amount: Balance,
+            amount_0: Balance,
+            amount_1: Balance,
+        ) {
+            self.env().emit_event(Burn {
+                owner,
+                tick_lower,
+                tick_upper,
+                amount,
+                amount_0,
+                amount_1,
+            });
+        }
+
         // fn _emit_transfer_event(
         //     &self,
         //     from: Option<AccountId>,
@@ -305,8 +374,18 @@ pub mod pool {
 
     impl PoolContract {
         #[ink(constructor)]
-        pub fn new() -> Self {
-            ink_lang::codegen::initialize_contract(|instance: &mut Self| {})
+        pub fn new(token_0: AccountId, token_1: AccountId, fee: u32, tick_spacing: i32) -> Self {
+            ink_lang::codegen::initialize_contract(|instance: &mut Self| {
+                // the pool is only ever deployed by the factory, which is the caller of the constructor
+                instance.pool.factory = instance.env().caller();
+                instance.pool.token_0 = token_0;
+                instance.pool.token_1 = token_1;
+                instance.pool.fee = fee;
+                instance.pool.tick_spacing = tick_spacing;
+                instance.pool.max_liquidity_per_tick =
+                    tick_spacing_to_max_liquidity_per_tick(tick_spacing);
+                instance.pool.version = data::STORAGE_VERSION;
+            })
         }
     }
 }
@@ -0,0 +1,28 @@
+use crate::ensure;
+use primitive_types::U256;
+
+// refer https://github.com/Uniswap/v3-core/blob/main/contracts/libraries/BitMath.sol
+
+/// Returns the index of the most significant bit of the number,
+/// where the least significant bit is at index 0 and the most significant bit is at index 255
+///
+/// x >= 2**most_significant_bit(x) and x < 2**(most_significant_bit(x)+1)
+pub fn most_significant_bit(x: U256) -> Result<u8, BitMathError> {
+    ensure!(!x.is_zero(), BitMathError::ZeroValue);
+    Ok((x.bits() - 1) as u8)
+}
+
+/// Returns the index of the least significant bit of the number,
+/// where the least significant bit is at index 0 and the most significant bit is at index 255
+///
+/// (x & 2**least_significant_bit(x)) != 0 and (x & (2**(least_significant_bit(x)) - 1)) == 0
+pub fn least_significant_bit(x: U256) -> Result<u8, BitMathError> {
+    ensure!(!x.is_zero(), BitMathError::ZeroValue);
+    Ok(x.trailing_zeros() as u8)
+}
+
+#[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub enum BitMathError {
+    ZeroValue,
+}
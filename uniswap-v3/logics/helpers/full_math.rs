@@ -0,0 +1,121 @@
+use crate::ensure;
+use primitive_types::{U256, U512};
+
+// refer https://github.com/Uniswap/v3-core/blob/main/contracts/libraries/FullMath.sol
+// Solidity needs the Chinese Remainder Theorem trick to keep the 512-bit intermediate,
+// here the product is widened to U512 instead, which yields the same exact result.
+
+/// Calculates floor(a×b÷denominator) with full precision.
+///
+/// Fails if the result overflows a U256 or denominator == 0.
+pub fn mul_div(a: U256, b: U256, denominator: U256) -> Result<U256, FullMathError> {
+    ensure!(!denominator.is_zero(), FullMathError::DivByZero);
+    let result = a.full_mul(b) / U512::from(denominator);
+    U256::try_from(result).map_err(|_| FullMathError::MulDivOverflow)
+}
+
+/// Calculates ceil(a×b÷denominator) with full precision.
+///
+/// Fails if the result overflows a U256 or denominator == 0.
+pub fn mul_div_rounding_up(a: U256, b: U256, denominator: U256) -> Result<U256, FullMathError> {
+    let result = mul_div(a, b, denominator)?;
+    if (a.full_mul(b) % U512::from(denominator)).is_zero() {
+        return Ok(result);
+    }
+    ensure!(result < U256::MAX, FullMathError::MulDivOverflow);
+    Ok(result + 1)
+}
+
+#[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub enum FullMathError {
+    DivByZero,
+    MulDivOverflow,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::math::Q128;
+
+    #[test]
+    fn mul_div_fails() {
+        assert_eq!(
+            mul_div(Q128, Q128, U256::zero()),
+            Err(FullMathError::DivByZero)
+        );
+        assert_eq!(
+            mul_div(Q128, Q128, U256::one()),
+            Err(FullMathError::MulDivOverflow)
+        );
+        assert_eq!(
+            mul_div(U256::MAX, U256::MAX, U256::MAX - 1),
+            Err(FullMathError::MulDivOverflow)
+        );
+    }
+
+    #[test]
+    fn mul_div_keeps_the_phantom_overflow() {
+        assert_eq!(mul_div(U256::MAX, U256::MAX, U256::MAX), Ok(U256::MAX));
+        assert_eq!(
+            mul_div(
+                Q128,
+                U256::from(50) * Q128 / 100,
+                U256::from(150) * Q128 / 100
+            ),
+            Ok(Q128 / 3)
+        );
+        assert_eq!(
+            mul_div(Q128, U256::from(35) * Q128, U256::from(8) * Q128),
+            Ok(U256::from(4375) * Q128 / 1000)
+        );
+        assert_eq!(
+            mul_div(Q128, U256::from(1000) * Q128, U256::from(3000) * Q128),
+            Ok(Q128 / 3)
+        );
+    }
+
+    #[test]
+    fn mul_div_rounding_up_fails() {
+        assert_eq!(
+            mul_div_rounding_up(Q128, Q128, U256::zero()),
+            Err(FullMathError::DivByZero)
+        );
+        assert_eq!(
+            mul_div_rounding_up(U256::MAX, U256::MAX, U256::MAX - 1),
+            Err(FullMathError::MulDivOverflow)
+        );
+        // the floor fits but rounding up overflows
+        assert_eq!(
+            mul_div_rounding_up(
+                U256::from(535006138814359u64),
+                U256::from_dec_str(
+                    "432862656469423142931042426214547535783388063929571229938474969"
+                )
+                .unwrap(),
+                U256::from(2)
+            ),
+            Err(FullMathError::MulDivOverflow)
+        );
+    }
+
+    #[test]
+    fn mul_div_rounding_up_rounds() {
+        assert_eq!(
+            mul_div_rounding_up(U256::MAX, U256::MAX, U256::MAX),
+            Ok(U256::MAX)
+        );
+        assert_eq!(
+            mul_div_rounding_up(
+                Q128,
+                U256::from(50) * Q128 / 100,
+                U256::from(150) * Q128 / 100
+            ),
+            Ok(Q128 / 3 + 1)
+        );
+        assert_eq!(
+            mul_div_rounding_up(U256::from(1000), U256::from(3), U256::from(3)),
+            Ok(U256::from(1000))
+        );
+    }
+}
@@ -1,3 +1,10 @@
+use ink_env::{
+    hash::{Blake2x256, HashOutput},
+    Hash,
+};
+use openbrush::traits::AccountId;
+use scale::Encode;
+
 // /// Evaluate `$x:expr` and if not true return `Err($y:expr)`.
 // ///
 // /// Used as `ensure!(expression_to_ensure, expression_to_return_on_false)`.
@@ -10,6 +17,84 @@ macro_rules! ensure {
     }};
 }
 
+/// Returns the salt the factory instantiates the pool of (token_0, token_1, fee) with
+///
+/// The tokens must be sorted, i.e. token_0 < token_1.
+pub fn pool_salt(token_0: AccountId, token_1: AccountId, fee: u32) -> [u8; 32] {
+    let mut output = <Blake2x256 as HashOutput>::Type::default();
+    ink_env::hash_encoded::<Blake2x256, _>(&(token_0, token_1, fee), &mut output);
+    output
+}
+
+/// Selector of the pool contract `new` constructor, the first 4 bytes of blake2_256("new")
+pub const POOL_CONSTRUCTOR_SELECTOR: [u8; 4] = [0x9b, 0xae, 0x9d, 0x5e];
+
+/// Fee tiers and their tick spacing enabled by the factory constructor
+pub const DEFAULT_FEE_AMOUNTS: [(u32, i32); 3] = [(500, 10), (3000, 60), (10000, 200)];
+
+/// Deterministically computes the pool address given the factory, pool code hash and PoolKey
+///
+/// Like the Uniswap PoolAddress library, this finds a pool without making cross contract calls.
+/// The tick spacing is looked up in `DEFAULT_FEE_AMOUNTS`, so `None` is returned for fee tiers
+/// enabled later through `Factory::enable_fee_amount`, whose pools are found with
+/// `compute_pool_address_with_tick_spacing`.
+pub fn compute_pool_address(
+    factory: AccountId,
+    code_hash: Hash,
+    token_a: AccountId,
+    token_b: AccountId,
+    fee: u32,
+) -> Option<AccountId> {
+    let (_, tick_spacing) = DEFAULT_FEE_AMOUNTS
+        .iter()
+        .find(|(fee_amount, _)| *fee_amount == fee)?;
+    Some(compute_pool_address_with_tick_spacing(
+        factory,
+        code_hash,
+        token_a,
+        token_b,
+        fee,
+        *tick_spacing,
+    ))
+}
+
+/// Deterministically computes the pool address given the factory, pool code hash, PoolKey and the
+/// tick spacing of the fee tier
+///
+/// The contracts pallet derives the address as
+/// blake2_256(encode(("contract_addr_v1", deployer, code_hash, input_data, salt))), where the input
+/// data is the selector of the pool constructor followed by its encoded arguments, as done by the
+/// `DefaultAddressGenerator` of pallet-contracts.
+pub fn compute_pool_address_with_tick_spacing(
+    factory: AccountId,
+    code_hash: Hash,
+    token_a: AccountId,
+    token_b: AccountId,
+    fee: u32,
+    tick_spacing: i32,
+) -> AccountId {
+    let (token_0, token_1) = if token_a < token_b {
+        (token_a, token_b)
+    } else {
+        (token_b, token_a)
+    };
+    let salt = pool_salt(token_0, token_1, fee);
+    let mut input_data = POOL_CONSTRUCTOR_SELECTOR.to_vec();
+    (token_0, token_1, fee, tick_spacing).encode_to(&mut input_data);
+    let mut output = <Blake2x256 as HashOutput>::Type::default();
+    ink_env::hash_encoded::<Blake2x256, _>(
+        &(
+            b"contract_addr_v1",
+            factory,
+            code_hash,
+            &input_data[..],
+            &salt[..],
+        ),
+        &mut output,
+    );
+    output.into()
+}
+
 // pub fn sort_tokens(
 //     token_a: AccountId,
 //     token_b: AccountId,
@@ -210,3 +295,69 @@ macro_rules! ensure {
 //     InvalidPath,
 //     SubUnderFlow,
 // }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pool_salt_is_distinct_per_fee_tier() {
+        let (token_0, token_1) = (AccountId::from([3; 32]), AccountId::from([4; 32]));
+        let salts: Vec<_> = DEFAULT_FEE_AMOUNTS
+            .iter()
+            .map(|(fee, _)| pool_salt(token_0, token_1, *fee))
+            .collect();
+        assert_ne!(salts[0], salts[1]);
+        assert_ne!(salts[1], salts[2]);
+        assert_ne!(salts[0], salts[2]);
+    }
+
+    #[test]
+    fn compute_pool_address_ignores_token_order() {
+        let (factory, code_hash) = (AccountId::from([1; 32]), Hash::from([2; 32]));
+        let (token_a, token_b) = (AccountId::from([4; 32]), AccountId::from([3; 32]));
+        assert_eq!(
+            compute_pool_address(factory, code_hash, token_a, token_b, 500),
+            compute_pool_address(factory, code_hash, token_b, token_a, 500)
+        );
+        assert_ne!(
+            compute_pool_address(factory, code_hash, token_a, token_b, 500),
+            compute_pool_address(factory, code_hash, token_a, token_b, 3000)
+        );
+    }
+
+    #[test]
+    fn compute_pool_address_needs_the_tick_spacing_of_new_fee_tiers() {
+        let (factory, code_hash) = (AccountId::from([1; 32]), Hash::from([2; 32]));
+        let (token_0, token_1) = (AccountId::from([3; 32]), AccountId::from([4; 32]));
+        assert_eq!(
+            compute_pool_address(factory, code_hash, token_0, token_1, 100),
+            None
+        );
+        assert_eq!(
+            compute_pool_address(factory, code_hash, token_0, token_1, 3000),
+            Some(compute_pool_address_with_tick_spacing(
+                factory, code_hash, token_0, token_1, 3000, 60
+            ))
+        );
+    }
+
+    #[test]
+    fn compute_pool_address_matches_contract_addr_v1() {
+        // blake2_256(b"contract_addr_v1" ++ factory ++ code_hash ++ compact(76) ++ input_data
+        // ++ compact(32) ++ salt), computed independently of the SCALE codec
+        let address = compute_pool_address(
+            AccountId::from([1; 32]),
+            Hash::from([2; 32]),
+            AccountId::from([4; 32]),
+            AccountId::from([3; 32]),
+            3000,
+        );
+        let expected: [u8; 32] = [
+            0x3d, 0x97, 0xb0, 0xe2, 0x99, 0xe0, 0x3d, 0x80, 0x75, 0x7c, 0x09, 0x04, 0x15, 0xa3,
+            0x03, 0xcd, 0x0b, 0xbb, 0x6f, 0x64, 0xe8, 0x47, 0x0d, 0x56, 0xc6, 0x02, 0x60, 0x0e,
+            0xf6, 0x80, 0x6c, 0x35,
+        ];
+        assert_eq!(address, Some(AccountId::from(expected)));
+    }
+}
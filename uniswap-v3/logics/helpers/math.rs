@@ -1,5 +1,44 @@
 use primitive_types::U256;
 
+// refer https://github.com/Uniswap/v3-core/blob/main/contracts/libraries/FixedPoint96.sol
+pub const RESOLUTION_96: u8 = 96;
+pub const Q96: U256 = U256([0, 1 << 32, 0, 0]);
+
+// refer https://github.com/Uniswap/v3-core/blob/main/contracts/libraries/FixedPoint128.sol
+pub const Q128: U256 = U256([0, 0, 1, 0]);
+
 pub fn casted_mul(a: u128, b: u128) -> U256 {
     U256::from(a) * U256::from(b)
 }
+
+/// Unchecked uint256 arithmetic as in Solidity < 0.8, which U256 only offers through `overflowing_*`
+///
+/// Used by the fee growth and seconds per liquidity accumulators, whose values only have relative meaning.
+pub trait WrappingMath {
+    fn wrapping_add(self, other: Self) -> Self;
+    fn wrapping_sub(self, other: Self) -> Self;
+}
+
+impl WrappingMath for U256 {
+    #[inline]
+    fn wrapping_add(self, other: U256) -> U256 {
+        self.overflowing_add(other).0
+    }
+
+    #[inline]
+    fn wrapping_sub(self, other: U256) -> U256 {
+        self.overflowing_sub(other).0
+    }
+}
+
+// refer https://github.com/Uniswap/v3-core/blob/main/contracts/libraries/UnsafeMath.sol
+/// Returns ceil(x / y), division by zero panics
+#[inline]
+pub fn div_rounding_up(x: U256, y: U256) -> U256 {
+    let quotient = x / y;
+    if (x % y).is_zero() {
+        quotient
+    } else {
+        quotient + 1
+    }
+}
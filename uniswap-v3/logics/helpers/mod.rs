@@ -1,4 +1,15 @@
+pub mod bit_math;
+pub mod full_math;
 pub mod helper;
 pub mod liquidity_helper;
 pub mod math;
+pub mod oracle;
+pub mod position;
+pub mod sqrt_price_math;
+pub mod swap_math;
+#[cfg(test)]
+pub mod test_helpers;
+pub mod tick;
+pub mod tick_bitmap;
+pub mod tick_math;
 pub mod transfer_helper;
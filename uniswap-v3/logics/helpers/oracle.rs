@@ -0,0 +1,453 @@
+use crate::{ensure, helpers::math::WrappingMath, impls::pool::data_struct::Observation};
+use ink_prelude::vec::Vec;
+use openbrush::storage::Mapping;
+use primitive_types::U256;
+
+// refer https://github.com/Uniswap/v3-core/blob/main/contracts/libraries/Oracle.sol
+// Provides price and liquidity data useful for a wide variety of system designs.
+// Instances of stored oracle data, "observations", are collected in the oracle array, a ring buffer of up to
+// 65535 slots kept in the pool's `observations` mapping and indexed by `Slot::observation_index`.
+// Every pool is initialized with an oracle array length of 1. Anyone can pay to increase the maximum length of
+// the oracle array, new slots are added when the array is fully populated.
+// Observations are overwritten when the full length of the oracle array is populated.
+
+/// Transforms a previous observation into a new observation, given the passage of time and the current tick and
+/// liquidity values
+///
+/// `block_timestamp` must be chronologically equal to or greater than `last.block_timestamp`, safe for 0 or 1
+/// overflows.
+pub fn transform(
+    last: &Observation,
+    block_timestamp: u32,
+    tick: i32,
+    liquidity: u128,
+) -> Observation {
+    let delta = block_timestamp.wrapping_sub(last.block_timestamp);
+    let liquidity = if liquidity > 0 { liquidity } else { 1 };
+    Observation {
+        block_timestamp,
+        tick_cumulative: last
+            .tick_cumulative
+            .wrapping_add((tick as i64).wrapping_mul(delta as i64)),
+        seconds_per_liquidity_cumulative_x128: U256(last.seconds_per_liquidity_cumulative_x128)
+            .wrapping_add((U256::from(delta) << 128) / U256::from(liquidity))
+            .0,
+        initialized: true,
+    }
+}
+
+/// Initialize the oracle array by writing the first slot. Called once for the lifecycle of the observations array
+///
+/// Returns the number of populated elements in the oracle array and the new length of the oracle array,
+/// independent of population.
+pub fn initialize(observations: &mut Mapping<u16, Observation>, time: u32) -> (u16, u16) {
+    observations.insert(
+        &0,
+        &Observation {
+            block_timestamp: time,
+            tick_cumulative: 0,
+            seconds_per_liquidity_cumulative_x128: [0; 4],
+            initialized: true,
+        },
+    );
+    (1, 1)
+}
+
+/// Writes an oracle observation to the array
+///
+/// Writable at most once per block. `index` represents the most recently written element. `cardinality` and
+/// `index` must be tracked externally. If the index is at the end of the allowable array length (according to
+/// cardinality), and the next cardinality is greater than the current one, cardinality may be increased. This
+/// restriction is created to preserve ordering.
+/// Returns the new index of the most recently written element in the oracle array and the new length of the
+/// oracle array, independent of population.
+pub fn write(
+    observations: &mut Mapping<u16, Observation>,
+    index: u16,
+    block_timestamp: u32,
+    tick: i32,
+    liquidity: u128,
+    cardinality: u16,
+    cardinality_next: u16,
+) -> (u16, u16) {
+    let last = observations.get(&index).unwrap_or_default();
+
+    // early return if we've already written an observation this block
+    if last.block_timestamp == block_timestamp {
+        return (index, cardinality);
+    }
+
+    // if the conditions are right, we can bump the cardinality
+    let cardinality_updated = if cardinality_next > cardinality && index == cardinality - 1 {
+        cardinality_next
+    } else {
+        cardinality
+    };
+
+    let index_updated = ((index as u32 + 1) % cardinality_updated as u32) as u16;
+    observations.insert(
+        &index_updated,
+        &transform(&last, block_timestamp, tick, liquidity),
+    );
+    (index_updated, cardinality_updated)
+}
+
+/// Prepares the oracle array to store up to `next` observations
+///
+/// Returns the next length of the oracle array, which will be populated as new observations are written.
+pub fn grow(
+    observations: &mut Mapping<u16, Observation>,
+    current: u16,
+    next: u16,
+) -> Result<u16, OracleError> {
+    ensure!(current > 0, OracleError::NotInitialized);
+    // no-op if the passed next value isn't greater than the current next value
+    if next <= current {
+        return Ok(current);
+    }
+    // store in each slot to prevent fresh storage writes in swaps
+    // this data will not be used because the initialized boolean is still false
+    for i in current..next {
+        observations.insert(
+            &i,
+            &Observation {
+                block_timestamp: 1,
+                ..Default::default()
+            },
+        );
+    }
+    Ok(next)
+}
+
+/// comparator for 32-bit timestamps
+///
+/// safe for 0 or 1 overflows, a and b _must_ be chronologically before or equal to time.
+/// Returns whether `a` is chronologically <= `b`.
+#[inline]
+fn lte(time: u32, a: u32, b: u32) -> bool {
+    // if there hasn't been overflow, no need to adjust
+    if a <= time && b <= time {
+        return a <= b;
+    }
+
+    let a_adjusted = if a > time {
+        a as u64
+    } else {
+        a as u64 + (1 << 32)
+    };
+    let b_adjusted = if b > time {
+        b as u64
+    } else {
+        b as u64 + (1 << 32)
+    };
+
+    a_adjusted <= b_adjusted
+}
+
+/// Fetches the observations before_or_at and at_or_after a target, i.e. where [before_or_at, at_or_after] is
+/// satisfied. The result may be the same observation, or adjacent observations.
+///
+/// The answer must be contained in the array, used when the target is located within the stored observation
+/// boundaries: older than the most recent observation and younger, or the same age as, the oldest observation.
+fn binary_search(
+    observations: &Mapping<u16, Observation>,
+    time: u32,
+    target: u32,
+    index: u16,
+    cardinality: u16,
+) -> (Observation, Observation) {
+    let cardinality = cardinality as u32;
+    // oldest observation
+    let mut l = (index as u32 + 1) % cardinality;
+    // newest observation
+    let mut r = l + cardinality - 1;
+    loop {
+        let i = (l + r) / 2;
+
+        let before_or_at = observations
+            .get(&((i % cardinality) as u16))
+            .unwrap_or_default();
+
+        // we've landed on an uninitialized tick, keep searching higher (more recently)
+        if !before_or_at.initialized {
+            l = i + 1;
+            continue;
+        }
+
+        let at_or_after = observations
+            .get(&(((i + 1) % cardinality) as u16))
+            .unwrap_or_default();
+
+        let target_at_or_after = lte(time, before_or_at.block_timestamp, target);
+
+        // check if we've found the answer!
+        if target_at_or_after && lte(time, target, at_or_after.block_timestamp) {
+            return (before_or_at, at_or_after);
+        }
+
+        if !target_at_or_after {
+            r = i - 1;
+        } else {
+            l = i + 1;
+        }
+    }
+}
+
+/// Fetches the observations before_or_at and at_or_after a given target, i.e. where [before_or_at, at_or_after]
+/// is satisfied
+///
+/// Assumes there is at least 1 initialized observation.
+/// Used by observe_single() to compute the counterfactual accumulator values as of a given block timestamp.
+#[allow(clippy::too_many_arguments)]
+fn get_surrounding_observations(
+    observations: &Mapping<u16, Observation>,
+    time: u32,
+    target: u32,
+    tick: i32,
+    index: u16,
+    liquidity: u128,
+    cardinality: u16,
+) -> Result<(Observation, Observation), OracleError> {
+    // optimistically set before to the newest observation
+    let before_or_at = observations.get(&index).unwrap_or_default();
+
+    // if the target is chronologically at or after the newest observation, we can early return
+    if lte(time, before_or_at.block_timestamp, target) {
+        if before_or_at.block_timestamp == target {
+            // if newest observation equals target, we're in the same block, so we can ignore at_or_after
+            return Ok((before_or_at, Observation::default()));
+        } else {
+            // otherwise, we need to transform
+            let at_or_after = transform(&before_or_at, target, tick, liquidity);
+            return Ok((before_or_at, at_or_after));
+        }
+    }
+
+    // now, set before to the oldest observation
+    let mut before_or_at = observations
+        .get(&(((index as u32 + 1) % cardinality as u32) as u16))
+        .unwrap_or_default();
+    if !before_or_at.initialized {
+        before_or_at = observations.get(&0).unwrap_or_default();
+    }
+
+    // ensure that the target is chronologically at or after the oldest observation
+    ensure!(
+        lte(time, before_or_at.block_timestamp, target),
+        OracleError::TargetTooOld
+    );
+
+    // if we've reached this point, we have to binary search
+    Ok(binary_search(
+        observations,
+        time,
+        target,
+        index,
+        cardinality,
+    ))
+}
+
+/// Returns the accumulator values as of each time seconds ago from the given time in the array of
+/// `seconds_ago`
+///
+/// Reverts if `seconds_ago` > oldest observation. 0 may be passed as `seconds_ago` to return the current
+/// cumulative values. If called with a timestamp falling between two observations, returns the counterfactual
+/// accumulator values at exactly the timestamp between the two observations.
+#[allow(clippy::too_many_arguments)]
+pub fn observe_single(
+    observations: &Mapping<u16, Observation>,
+    time: u32,
+    seconds_ago: u32,
+    tick: i32,
+    index: u16,
+    liquidity: u128,
+    cardinality: u16,
+) -> Result<(i64, U256), OracleError> {
+    if seconds_ago == 0 {
+        let mut last = observations.get(&index).unwrap_or_default();
+        if last.block_timestamp != time {
+            last = transform(&last, time, tick, liquidity);
+        }
+        return Ok((
+            last.tick_cumulative,
+            U256(last.seconds_per_liquidity_cumulative_x128),
+        ));
+    }
+
+    let target = time.wrapping_sub(seconds_ago);
+
+    let (before_or_at, at_or_after) = get_surrounding_observations(
+        observations,
+        time,
+        target,
+        tick,
+        index,
+        liquidity,
+        cardinality,
+    )?;
+
+    if target == before_or_at.block_timestamp {
+        // we're at the left boundary
+        Ok((
+            before_or_at.tick_cumulative,
+            U256(before_or_at.seconds_per_liquidity_cumulative_x128),
+        ))
+    } else if target == at_or_after.block_timestamp {
+        // we're at the right boundary
+        Ok((
+            at_or_after.tick_cumulative,
+            U256(at_or_after.seconds_per_liquidity_cumulative_x128),
+        ))
+    } else {
+        // we're in the middle
+        let observation_time_delta = at_or_after
+            .block_timestamp
+            .wrapping_sub(before_or_at.block_timestamp);
+        let target_delta = target.wrapping_sub(before_or_at.block_timestamp);
+        let seconds_per_liquidity_before_x128 =
+            U256(before_or_at.seconds_per_liquidity_cumulative_x128);
+        // the delta of two adjacent observations is below 2^160, so the product below can't overflow
+        let seconds_per_liquidity_delta = U256(at_or_after.seconds_per_liquidity_cumulative_x128)
+            .wrapping_sub(seconds_per_liquidity_before_x128);
+        Ok((
+            before_or_at.tick_cumulative.wrapping_add(
+                (at_or_after
+                    .tick_cumulative
+                    .wrapping_sub(before_or_at.tick_cumulative)
+                    / observation_time_delta as i64)
+                    .wrapping_mul(target_delta as i64),
+            ),
+            seconds_per_liquidity_before_x128.wrapping_add(
+                seconds_per_liquidity_delta * U256::from(target_delta)
+                    / U256::from(observation_time_delta),
+            ),
+        ))
+    }
+}
+
+/// Returns the accumulator values as of each time seconds ago from the given time in the array of
+/// `seconds_agos`
+///
+/// Fails if any of `seconds_agos` is older than the oldest observation.
+pub fn observe(
+    observations: &Mapping<u16, Observation>,
+    time: u32,
+    seconds_agos: &[u32],
+    tick: i32,
+    index: u16,
+    liquidity: u128,
+    cardinality: u16,
+) -> Result<(Vec<i64>, Vec<U256>), OracleError> {
+    ensure!(cardinality > 0, OracleError::NotInitialized);
+
+    let mut tick_cumulatives = Vec::with_capacity(seconds_agos.len());
+    let mut seconds_per_liquidity_cumulative_x128s = Vec::with_capacity(seconds_agos.len());
+    for seconds_ago in seconds_agos {
+        let (tick_cumulative, seconds_per_liquidity_cumulative_x128) = observe_single(
+            observations,
+            time,
+            *seconds_ago,
+            tick,
+            index,
+            liquidity,
+            cardinality,
+        )?;
+        tick_cumulatives.push(tick_cumulative);
+        seconds_per_liquidity_cumulative_x128s.push(seconds_per_liquidity_cumulative_x128);
+    }
+    Ok((tick_cumulatives, seconds_per_liquidity_cumulative_x128s))
+}
+
+#[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub enum OracleError {
+    NotInitialized,
+    TargetTooOld,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[ink_lang::test]
+    fn write_is_a_no_op_within_the_same_block() {
+        let mut observations = Mapping::default();
+        assert_eq!(initialize(&mut observations, 5), (1, 1));
+        assert_eq!(write(&mut observations, 0, 5, 3, 2, 1, 1), (0, 1));
+        assert_eq!(observations.get(&0).unwrap().tick_cumulative, 0);
+    }
+
+    #[ink_lang::test]
+    fn write_overwrites_the_single_slot() {
+        let mut observations = Mapping::default();
+        initialize(&mut observations, 5);
+        assert_eq!(write(&mut observations, 0, 6, 3, 2, 1, 1), (0, 1));
+        let observation = observations.get(&0).unwrap();
+        assert_eq!(observation.block_timestamp, 6);
+        assert_eq!(observation.tick_cumulative, 3);
+        // one second at a liquidity of 2
+        assert_eq!(
+            U256(observation.seconds_per_liquidity_cumulative_x128),
+            U256::one() << 127
+        );
+    }
+
+    #[ink_lang::test]
+    fn transform_accumulates_seconds_without_liquidity() {
+        let last = Observation {
+            initialized: true,
+            ..Default::default()
+        };
+        // without liquidity every second counts as a full second per unit of liquidity
+        let observation = transform(&last, u32::MAX, 0, 0);
+        assert_eq!(
+            U256(observation.seconds_per_liquidity_cumulative_x128),
+            U256::from(u32::MAX) << 128
+        );
+        let observation = transform(&last, 10, 0, 1);
+        assert_eq!(
+            U256(observation.seconds_per_liquidity_cumulative_x128),
+            U256::from(10) << 128
+        );
+    }
+
+    #[ink_lang::test]
+    fn grow_fails_before_initialize() {
+        let mut observations = Mapping::default();
+        assert_eq!(
+            grow(&mut observations, 0, 3),
+            Err(OracleError::NotInitialized)
+        );
+    }
+
+    #[ink_lang::test]
+    fn observe_interpolates_between_observations() {
+        let mut observations = Mapping::default();
+        initialize(&mut observations, 0);
+        assert_eq!(write(&mut observations, 0, 3, 2, 5, 1, 3), (1, 3));
+        assert_eq!(write(&mut observations, 1, 6, -5, 5, 3, 3), (2, 3));
+        assert_eq!(write(&mut observations, 2, 10, 1, 5, 3, 3), (0, 3));
+        // the ring buffer now holds [t=10, tc=-5], [t=3, tc=6], [t=6, tc=-9]
+        let observe_at = |seconds_ago| {
+            observe_single(&observations, 12, seconds_ago, 1, 0, 5, 3).map(|(tick, _)| tick)
+        };
+        assert_eq!(observe_at(0), Ok(-3));
+        assert_eq!(observe_at(2), Ok(-5));
+        assert_eq!(observe_at(4), Ok(-7));
+        assert_eq!(observe_at(6), Ok(-9));
+        assert_eq!(observe_at(7), Ok(-4));
+        assert_eq!(observe_at(9), Ok(6));
+        assert_eq!(observe_at(10), Err(OracleError::TargetTooOld));
+
+        let (tick_cumulatives, _) = observe(&observations, 12, &[0, 4, 9], 1, 0, 5, 3).unwrap();
+        assert_eq!(tick_cumulatives, [-3, -7, 6]);
+        assert_eq!(
+            observe(&observations, 12, &[0, 10], 1, 0, 5, 3),
+            Err(OracleError::TargetTooOld)
+        );
+        assert_eq!(
+            observe(&observations, 12, &[0], 1, 0, 5, 0),
+            Err(OracleError::NotInitialized)
+        );
+    }
+}
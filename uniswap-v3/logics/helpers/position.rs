@@ -0,0 +1,184 @@
+use crate::{
+    ensure,
+    helpers::{
+        full_math::{mul_div, FullMathError},
+        liquidity_helper::{add_delta, LiquidityHelperError},
+        math::{WrappingMath, Q128},
+    },
+    impls::pool::data_struct::PositionInfo,
+};
+use ink_env::hash::Blake2x256;
+use openbrush::{storage::Mapping, traits::AccountId};
+use primitive_types::U256;
+
+// refer https://github.com/Uniswap/v3-core/blob/main/contracts/libraries/Position.sol
+// Positions represent an owner address' liquidity between a lower and upper tick boundary.
+// Positions store additional state for tracking fees owed to the position.
+
+/// Returns the key of the position given an owner and position boundaries
+pub fn key(owner: AccountId, tick_lower: i32, tick_upper: i32) -> [u8; 32] {
+    let mut output = [0u8; 32];
+    ink_env::hash_encoded::<Blake2x256, _>(&(owner, tick_lower, tick_upper), &mut output);
+    output
+}
+
+/// Returns the position info struct given an owner and position boundaries
+pub fn get(
+    positions: &Mapping<[u8; 32], PositionInfo>,
+    owner: AccountId,
+    tick_lower: i32,
+    tick_upper: i32,
+) -> PositionInfo {
+    positions
+        .get(&key(owner, tick_lower, tick_upper))
+        .unwrap_or_default()
+}
+
+/// Credits accumulated fees to a user's position
+///
+/// Returns the updated position. Fails with `PositionError::NoLiquidity` on a poke of a position without
+/// liquidity.
+pub fn update(
+    positions: &mut Mapping<[u8; 32], PositionInfo>,
+    owner: AccountId,
+    tick_lower: i32,
+    tick_upper: i32,
+    liquidity_delta: i128,
+    fee_growth_inside_0x128: U256,
+    fee_growth_inside_1x128: U256,
+) -> Result<PositionInfo, PositionError> {
+    let key = key(owner, tick_lower, tick_upper);
+    let mut position = positions.get(&key).unwrap_or_default();
+
+    let liquidity_next = if liquidity_delta == 0 {
+        // disallow pokes for 0 liquidity positions
+        ensure!(position.liquidity > 0, PositionError::NoLiquidity);
+        position.liquidity
+    } else {
+        add_delta(position.liquidity, liquidity_delta)?
+    };
+
+    // calculate accumulated fees, truncated to 128 bits like Uniswap does
+    let tokens_owed_0 = mul_div(
+        fee_growth_inside_0x128.wrapping_sub(U256(position.fee_growth_inside_0_last_x128)),
+        U256::from(position.liquidity),
+        Q128,
+    )?
+    .low_u128();
+    let tokens_owed_1 = mul_div(
+        fee_growth_inside_1x128.wrapping_sub(U256(position.fee_growth_inside_1_last_x128)),
+        U256::from(position.liquidity),
+        Q128,
+    )?
+    .low_u128();
+
+    // update the position
+    position.liquidity = liquidity_next;
+    position.fee_growth_inside_0_last_x128 = fee_growth_inside_0x128.0;
+    position.fee_growth_inside_1_last_x128 = fee_growth_inside_1x128.0;
+    // overflow is acceptable, have to withdraw before you hit u128::MAX fees
+    position.tokens_owed_0 = position.tokens_owed_0.wrapping_add(tokens_owed_0);
+    position.tokens_owed_1 = position.tokens_owed_1.wrapping_add(tokens_owed_1);
+
+    positions.insert(&key, &position);
+    Ok(position)
+}
+
+#[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub enum PositionError {
+    NoLiquidity,
+    FullMathError(FullMathError),
+    LiquidityHelperError(LiquidityHelperError),
+}
+
+impl From<FullMathError> for PositionError {
+    fn from(error: FullMathError) -> Self {
+        PositionError::FullMathError(error)
+    }
+}
+
+impl From<LiquidityHelperError> for PositionError {
+    fn from(error: LiquidityHelperError) -> Self {
+        PositionError::LiquidityHelperError(error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const OWNER: [u8; 32] = [1; 32];
+
+    #[ink_lang::test]
+    fn poke_of_an_empty_position_fails() {
+        let mut positions = Mapping::default();
+        assert_eq!(
+            update(
+                &mut positions,
+                OWNER.into(),
+                -60,
+                60,
+                0,
+                U256::zero(),
+                U256::zero()
+            )
+            .unwrap_err(),
+            PositionError::NoLiquidity
+        );
+    }
+
+    #[ink_lang::test]
+    fn credits_fee_growth_of_more_than_one_token_per_liquidity() {
+        let mut positions = Mapping::default();
+        let position = update(&mut positions, OWNER.into(), -60, 60, 1000, Q128, Q128).unwrap();
+        assert_eq!(
+            (
+                position.liquidity,
+                position.tokens_owed_0,
+                position.tokens_owed_1
+            ),
+            (1000, 0, 0)
+        );
+
+        let position = update(
+            &mut positions,
+            OWNER.into(),
+            -60,
+            60,
+            -400,
+            Q128 * 6 + Q128 / 2,
+            Q128 * 4,
+        )
+        .unwrap();
+        assert_eq!(
+            (
+                position.liquidity,
+                position.tokens_owed_0,
+                position.tokens_owed_1
+            ),
+            (600, 5500, 3000)
+        );
+        assert_eq!(get(&positions, OWNER.into(), -60, 60).liquidity, 600);
+        assert_eq!(get(&positions, [2; 32].into(), -60, 60).liquidity, 0);
+    }
+
+    #[ink_lang::test]
+    fn fee_growth_wraps_at_256_bits() {
+        let mut positions = Mapping::default();
+        let last = U256::MAX - Q128 + 1;
+        update(
+            &mut positions,
+            OWNER.into(),
+            -60,
+            60,
+            10,
+            last,
+            U256::zero(),
+        )
+        .unwrap();
+        let position =
+            update(&mut positions, OWNER.into(), -60, 60, 0, Q128, U256::zero()).unwrap();
+        assert_eq!(position.tokens_owed_0, 20);
+    }
+}
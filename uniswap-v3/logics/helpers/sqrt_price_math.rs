@@ -0,0 +1,385 @@
+use crate::{
+    ensure,
+    helpers::{
+        full_math::{mul_div, mul_div_rounding_up, FullMathError},
+        math::{div_rounding_up, Q96, RESOLUTION_96},
+    },
+};
+use primitive_types::U256;
+
+// refer https://github.com/Uniswap/v3-core/blob/main/contracts/libraries/SqrtPriceMath.sol
+
+/// Gets the next sqrt price given a delta of token0
+///
+/// Always rounds up, because in the exact output case (increasing price) we need to move the price at least
+/// far enough to get the desired output amount, and in the exact input case (decreasing price) we need to move the
+/// price less in order to not send too much output.
+/// The most precise formula for this is liquidity * sqrtPX96 / (liquidity +- amount * sqrtPX96),
+/// if this is impossible because of overflow, we calculate liquidity / (liquidity / sqrtPX96 +- amount).
+pub fn get_next_sqrt_price_from_amount0_rounding_up(
+    sqrt_px96: U256,
+    liquidity: u128,
+    amount: U256,
+    add: bool,
+) -> Result<U256, SqrtPriceMathError> {
+    // we short circuit amount == 0 because the result is otherwise not guaranteed to equal the input price
+    if amount.is_zero() {
+        return Ok(sqrt_px96);
+    }
+    let numerator1 = U256::from(liquidity) << RESOLUTION_96;
+
+    if add {
+        if let Some(product) = amount.checked_mul(sqrt_px96) {
+            if let Some(denominator) = numerator1.checked_add(product) {
+                // always fits in 160 bits
+                return Ok(mul_div_rounding_up(numerator1, sqrt_px96, denominator)?);
+            }
+        }
+        let denominator = (numerator1 / sqrt_px96)
+            .checked_add(amount)
+            .ok_or(SqrtPriceMathError::AddOverflow)?;
+        Ok(div_rounding_up(numerator1, denominator))
+    } else {
+        // if the product overflows, we know the denominator underflows
+        // in addition, we must check that the denominator does not underflow
+        let product = amount
+            .checked_mul(sqrt_px96)
+            .ok_or(SqrtPriceMathError::InsufficientLiquidity)?;
+        ensure!(
+            numerator1 > product,
+            SqrtPriceMathError::InsufficientLiquidity
+        );
+        let denominator = numerator1 - product;
+        to_uint160(mul_div_rounding_up(numerator1, sqrt_px96, denominator)?)
+    }
+}
+
+/// Gets the next sqrt price given a delta of token1
+///
+/// Always rounds down, because in the exact output case (decreasing price) we need to move the price at least
+/// far enough to get the desired output amount, and in the exact input case (increasing price) we need to move the
+/// price less in order to not send too much output.
+/// The formula we compute is within <1 wei of the lossless version: sqrtPX96 +- amount / liquidity
+pub fn get_next_sqrt_price_from_amount1_rounding_down(
+    sqrt_px96: U256,
+    liquidity: u128,
+    amount: U256,
+    add: bool,
+) -> Result<U256, SqrtPriceMathError> {
+    // if we're adding (subtracting), rounding down requires rounding the quotient down (up)
+    // in both cases, avoid a mul_div for most inputs
+    if add {
+        let quotient = if amount.bits() <= 160 {
+            (amount << RESOLUTION_96) / U256::from(liquidity)
+        } else {
+            mul_div(amount, Q96, U256::from(liquidity))?
+        };
+        to_uint160(
+            sqrt_px96
+                .checked_add(quotient)
+                .ok_or(SqrtPriceMathError::AddOverflow)?,
+        )
+    } else {
+        let quotient = if amount.bits() <= 160 {
+            div_rounding_up(amount << RESOLUTION_96, U256::from(liquidity))
+        } else {
+            mul_div_rounding_up(amount, Q96, U256::from(liquidity))?
+        };
+        ensure!(
+            sqrt_px96 > quotient,
+            SqrtPriceMathError::InsufficientLiquidity
+        );
+        // always fits 160 bits
+        Ok(sqrt_px96 - quotient)
+    }
+}
+
+/// Gets the next sqrt price given an input amount of token0 or token1
+///
+/// Fails if price or liquidity are 0, or if the next price is out of bounds.
+pub fn get_next_sqrt_price_from_input(
+    sqrt_px96: U256,
+    liquidity: u128,
+    amount_in: U256,
+    zero_for_one: bool,
+) -> Result<U256, SqrtPriceMathError> {
+    ensure!(!sqrt_px96.is_zero(), SqrtPriceMathError::ZeroPrice);
+    ensure!(liquidity > 0, SqrtPriceMathError::ZeroLiquidity);
+
+    // round to make sure that we don't pass the target price
+    if zero_for_one {
+        get_next_sqrt_price_from_amount0_rounding_up(sqrt_px96, liquidity, amount_in, true)
+    } else {
+        get_next_sqrt_price_from_amount1_rounding_down(sqrt_px96, liquidity, amount_in, true)
+    }
+}
+
+/// Gets the next sqrt price given an output amount of token0 or token1
+///
+/// Fails if price or liquidity are 0 or the next price is out of bounds.
+pub fn get_next_sqrt_price_from_output(
+    sqrt_px96: U256,
+    liquidity: u128,
+    amount_out: U256,
+    zero_for_one: bool,
+) -> Result<U256, SqrtPriceMathError> {
+    ensure!(!sqrt_px96.is_zero(), SqrtPriceMathError::ZeroPrice);
+    ensure!(liquidity > 0, SqrtPriceMathError::ZeroLiquidity);
+
+    // round to make sure that we pass the target price
+    if zero_for_one {
+        get_next_sqrt_price_from_amount1_rounding_down(sqrt_px96, liquidity, amount_out, false)
+    } else {
+        get_next_sqrt_price_from_amount0_rounding_up(sqrt_px96, liquidity, amount_out, false)
+    }
+}
+
+/// Gets the amount0 delta between two prices
+///
+/// Calculates liquidity / sqrt(lower) - liquidity / sqrt(upper),
+/// i.e. liquidity * (sqrt(upper) - sqrt(lower)) / (sqrt(upper) * sqrt(lower))
+pub fn get_amount0_delta(
+    sqrt_ratio_ax96: U256,
+    sqrt_ratio_bx96: U256,
+    liquidity: u128,
+    round_up: bool,
+) -> Result<U256, SqrtPriceMathError> {
+    let (sqrt_ratio_ax96, sqrt_ratio_bx96) = if sqrt_ratio_ax96 > sqrt_ratio_bx96 {
+        (sqrt_ratio_bx96, sqrt_ratio_ax96)
+    } else {
+        (sqrt_ratio_ax96, sqrt_ratio_bx96)
+    };
+    ensure!(!sqrt_ratio_ax96.is_zero(), SqrtPriceMathError::ZeroPrice);
+
+    let numerator1 = U256::from(liquidity) << RESOLUTION_96;
+    let numerator2 = sqrt_ratio_bx96 - sqrt_ratio_ax96;
+
+    if round_up {
+        Ok(div_rounding_up(
+            mul_div_rounding_up(numerator1, numerator2, sqrt_ratio_bx96)?,
+            sqrt_ratio_ax96,
+        ))
+    } else {
+        Ok(mul_div(numerator1, numerator2, sqrt_ratio_bx96)? / sqrt_ratio_ax96)
+    }
+}
+
+/// Gets the amount1 delta between two prices
+///
+/// Calculates liquidity * (sqrt(upper) - sqrt(lower))
+pub fn get_amount1_delta(
+    sqrt_ratio_ax96: U256,
+    sqrt_ratio_bx96: U256,
+    liquidity: u128,
+    round_up: bool,
+) -> Result<U256, SqrtPriceMathError> {
+    let (sqrt_ratio_ax96, sqrt_ratio_bx96) = if sqrt_ratio_ax96 > sqrt_ratio_bx96 {
+        (sqrt_ratio_bx96, sqrt_ratio_ax96)
+    } else {
+        (sqrt_ratio_ax96, sqrt_ratio_bx96)
+    };
+
+    if round_up {
+        Ok(mul_div_rounding_up(
+            U256::from(liquidity),
+            sqrt_ratio_bx96 - sqrt_ratio_ax96,
+            Q96,
+        )?)
+    } else {
+        Ok(mul_div(
+            U256::from(liquidity),
+            sqrt_ratio_bx96 - sqrt_ratio_ax96,
+            Q96,
+        )?)
+    }
+}
+
+/// Helper that gets the signed token0 delta for a liquidity change,
+/// rounded up when liquidity is added and down when it is removed
+pub fn get_amount0_delta_signed(
+    sqrt_ratio_ax96: U256,
+    sqrt_ratio_bx96: U256,
+    liquidity: i128,
+) -> Result<i128, SqrtPriceMathError> {
+    if liquidity < 0 {
+        Ok(-to_int128(get_amount0_delta(
+            sqrt_ratio_ax96,
+            sqrt_ratio_bx96,
+            liquidity.unsigned_abs(),
+            false,
+        )?)?)
+    } else {
+        to_int128(get_amount0_delta(
+            sqrt_ratio_ax96,
+            sqrt_ratio_bx96,
+            liquidity as u128,
+            true,
+        )?)
+    }
+}
+
+/// Helper that gets the signed token1 delta for a liquidity change,
+/// rounded up when liquidity is added and down when it is removed
+pub fn get_amount1_delta_signed(
+    sqrt_ratio_ax96: U256,
+    sqrt_ratio_bx96: U256,
+    liquidity: i128,
+) -> Result<i128, SqrtPriceMathError> {
+    if liquidity < 0 {
+        Ok(-to_int128(get_amount1_delta(
+            sqrt_ratio_ax96,
+            sqrt_ratio_bx96,
+            liquidity.unsigned_abs(),
+            false,
+        )?)?)
+    } else {
+        to_int128(get_amount1_delta(
+            sqrt_ratio_ax96,
+            sqrt_ratio_bx96,
+            liquidity as u128,
+            true,
+        )?)
+    }
+}
+
+#[inline]
+fn to_uint160(x: U256) -> Result<U256, SqrtPriceMathError> {
+    ensure!(x.bits() <= 160, SqrtPriceMathError::PriceOverflow);
+    Ok(x)
+}
+
+#[inline]
+fn to_int128(x: U256) -> Result<i128, SqrtPriceMathError> {
+    ensure!(x <= U256::from(i128::MAX), SqrtPriceMathError::CastOverflow);
+    Ok(x.low_u128() as i128)
+}
+
+#[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub enum SqrtPriceMathError {
+    ZeroPrice,
+    ZeroLiquidity,
+    InsufficientLiquidity,
+    AddOverflow,
+    PriceOverflow,
+    CastOverflow,
+    FullMathError(FullMathError),
+}
+
+impl From<FullMathError> for SqrtPriceMathError {
+    fn from(error: FullMathError) -> Self {
+        SqrtPriceMathError::FullMathError(error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::test_helpers::u256;
+
+    const E18: u128 = 1_000_000_000_000_000_000;
+
+    // encodePriceSqrt(1, 1) and encodePriceSqrt(121, 100)
+    fn price_1() -> U256 {
+        U256::one() << 96
+    }
+
+    fn price_121_100() -> U256 {
+        u256("87150978765690771352898345369")
+    }
+
+    #[test]
+    fn get_next_sqrt_price_from_input_fails() {
+        let amount = U256::from(E18 / 10);
+        assert_eq!(
+            get_next_sqrt_price_from_input(U256::zero(), E18, amount, true),
+            Err(SqrtPriceMathError::ZeroPrice)
+        );
+        assert_eq!(
+            get_next_sqrt_price_from_input(price_1(), 0, amount, true),
+            Err(SqrtPriceMathError::ZeroLiquidity)
+        );
+        // the input amount overflows the price
+        let max_price = (U256::one() << 160) - 1;
+        assert!(get_next_sqrt_price_from_input(max_price, 1024, U256::from(1024), false).is_err());
+    }
+
+    #[test]
+    fn get_next_sqrt_price_from_input_values() {
+        let amount = U256::from(E18 / 10);
+        assert_eq!(
+            get_next_sqrt_price_from_input(price_1(), E18, U256::zero(), true),
+            Ok(price_1())
+        );
+        assert_eq!(
+            get_next_sqrt_price_from_input(price_1(), E18, amount, false),
+            Ok(price_121_100())
+        );
+        assert_eq!(
+            get_next_sqrt_price_from_input(price_1(), E18, amount, true),
+            Ok(u256("72025602285694852357767227579"))
+        );
+        // any input amount cannot underflow the price
+        assert_eq!(
+            get_next_sqrt_price_from_input(U256::one(), 1, U256::MAX >> 1, true),
+            Ok(U256::one())
+        );
+    }
+
+    #[test]
+    fn get_next_sqrt_price_from_output_values() {
+        let amount = U256::from(E18 / 10);
+        let price = u256("20282409603651670423947251286016");
+        assert_eq!(
+            get_next_sqrt_price_from_output(price, 1024, U256::from(262143), true),
+            Ok(u256("77371252455336267181195264"))
+        );
+        // the output amount is exactly the virtual reserves of token1, or more than token0
+        assert!(get_next_sqrt_price_from_output(price, 1024, U256::from(262144), true).is_err());
+        assert!(get_next_sqrt_price_from_output(price, 1024, U256::from(4), false).is_err());
+        assert_eq!(
+            get_next_sqrt_price_from_output(price_1(), E18, amount, false),
+            Ok(u256("88031291682515930659493278152"))
+        );
+        assert_eq!(
+            get_next_sqrt_price_from_output(price_1(), E18, amount, true),
+            Ok(u256("71305346262837903834189555302"))
+        );
+    }
+
+    #[test]
+    fn get_amount_deltas() {
+        assert_eq!(
+            get_amount0_delta(price_1(), price_1(), E18, true),
+            Ok(U256::zero())
+        );
+        assert_eq!(
+            get_amount0_delta(price_1(), price_121_100(), E18, true),
+            Ok(U256::from(90909090909090910u128))
+        );
+        assert_eq!(
+            get_amount0_delta(price_1(), price_121_100(), E18, false),
+            Ok(U256::from(90909090909090909u128))
+        );
+        assert_eq!(
+            get_amount1_delta(price_1(), price_121_100(), E18, true),
+            Ok(U256::from(100000000000000000u128))
+        );
+        assert_eq!(
+            get_amount1_delta(price_1(), price_121_100(), E18, false),
+            Ok(U256::from(99999999999999999u128))
+        );
+        assert_eq!(
+            get_amount0_delta_signed(price_1(), price_121_100(), E18 as i128),
+            Ok(90909090909090910)
+        );
+        assert_eq!(
+            get_amount0_delta_signed(price_121_100(), price_1(), -(E18 as i128)),
+            Ok(-90909090909090909)
+        );
+        assert_eq!(
+            get_amount1_delta_signed(price_1(), price_121_100(), -(E18 as i128)),
+            Ok(-99999999999999999)
+        );
+    }
+}
@@ -0,0 +1,236 @@
+use crate::helpers::{
+    full_math::{mul_div, mul_div_rounding_up},
+    sqrt_price_math::{
+        get_amount0_delta, get_amount1_delta, get_next_sqrt_price_from_input,
+        get_next_sqrt_price_from_output, SqrtPriceMathError,
+    },
+};
+use primitive_types::U256;
+
+// refer https://github.com/Uniswap/v3-core/blob/main/contracts/libraries/SwapMath.sol
+
+/// Fees are expressed in hundredths of a bip, i.e. 1e-6
+pub const FEE_DENOMINATOR: u32 = 1_000_000;
+
+/// Computes the result of swapping some amount in, or amount out, given the parameters of the swap
+///
+/// The fee, plus the amount in, will never exceed the amount remaining if the swap's `amount_specified` is positive.
+/// `amount_remaining` is positive for an exact input swap and negative for an exact output swap.
+///
+/// Returns `(sqrt_ratio_next_x96, amount_in, amount_out, fee_amount)`: the price after swapping the amount in/out,
+/// not to exceed the price target, the amount to be swapped in of either token0 or token1 based on the direction
+/// of the swap, the amount to be received of either token0 or token1, and the amount of input that will be taken
+/// as a fee.
+pub fn compute_swap_step(
+    sqrt_ratio_current_x96: U256,
+    sqrt_ratio_target_x96: U256,
+    liquidity: u128,
+    amount_remaining: i128,
+    fee_pips: u32,
+) -> Result<(U256, U256, U256, U256), SqrtPriceMathError> {
+    let zero_for_one = sqrt_ratio_current_x96 >= sqrt_ratio_target_x96;
+    let exact_in = amount_remaining >= 0;
+    let amount_remaining_abs = U256::from(amount_remaining.unsigned_abs());
+
+    let sqrt_ratio_next_x96;
+    let mut amount_in = U256::zero();
+    let mut amount_out = U256::zero();
+
+    if exact_in {
+        let amount_remaining_less_fee = mul_div(
+            amount_remaining_abs,
+            U256::from(FEE_DENOMINATOR - fee_pips),
+            U256::from(FEE_DENOMINATOR),
+        )?;
+        amount_in = if zero_for_one {
+            get_amount0_delta(
+                sqrt_ratio_target_x96,
+                sqrt_ratio_current_x96,
+                liquidity,
+                true,
+            )?
+        } else {
+            get_amount1_delta(
+                sqrt_ratio_current_x96,
+                sqrt_ratio_target_x96,
+                liquidity,
+                true,
+            )?
+        };
+        sqrt_ratio_next_x96 = if amount_remaining_less_fee >= amount_in {
+            sqrt_ratio_target_x96
+        } else {
+            get_next_sqrt_price_from_input(
+                sqrt_ratio_current_x96,
+                liquidity,
+                amount_remaining_less_fee,
+                zero_for_one,
+            )?
+        };
+    } else {
+        amount_out = if zero_for_one {
+            get_amount1_delta(
+                sqrt_ratio_target_x96,
+                sqrt_ratio_current_x96,
+                liquidity,
+                false,
+            )?
+        } else {
+            get_amount0_delta(
+                sqrt_ratio_current_x96,
+                sqrt_ratio_target_x96,
+                liquidity,
+                false,
+            )?
+        };
+        sqrt_ratio_next_x96 = if amount_remaining_abs >= amount_out {
+            sqrt_ratio_target_x96
+        } else {
+            get_next_sqrt_price_from_output(
+                sqrt_ratio_current_x96,
+                liquidity,
+                amount_remaining_abs,
+                zero_for_one,
+            )?
+        };
+    }
+
+    let max = sqrt_ratio_target_x96 == sqrt_ratio_next_x96;
+
+    // get the input/output amounts
+    if zero_for_one {
+        if !(max && exact_in) {
+            amount_in =
+                get_amount0_delta(sqrt_ratio_next_x96, sqrt_ratio_current_x96, liquidity, true)?;
+        }
+        if !(max && !exact_in) {
+            amount_out = get_amount1_delta(
+                sqrt_ratio_next_x96,
+                sqrt_ratio_current_x96,
+                liquidity,
+                false,
+            )?;
+        }
+    } else {
+        if !(max && exact_in) {
+            amount_in =
+                get_amount1_delta(sqrt_ratio_current_x96, sqrt_ratio_next_x96, liquidity, true)?;
+        }
+        if !(max && !exact_in) {
+            amount_out = get_amount0_delta(
+                sqrt_ratio_current_x96,
+                sqrt_ratio_next_x96,
+                liquidity,
+                false,
+            )?;
+        }
+    }
+
+    // cap the output amount to not exceed the remaining output amount
+    if !exact_in && amount_out > amount_remaining_abs {
+        amount_out = amount_remaining_abs;
+    }
+
+    let fee_amount = if exact_in && sqrt_ratio_next_x96 != sqrt_ratio_target_x96 {
+        // we didn't reach the target, so take the remainder of the maximum input as fee
+        amount_remaining_abs - amount_in
+    } else {
+        mul_div_rounding_up(
+            amount_in,
+            U256::from(fee_pips),
+            U256::from(FEE_DENOMINATOR - fee_pips),
+        )?
+    };
+
+    Ok((sqrt_ratio_next_x96, amount_in, amount_out, fee_amount))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::test_helpers::u256;
+
+    const E18: i128 = 1_000_000_000_000_000_000;
+
+    #[test]
+    fn exact_amount_in_capped_at_price_target() {
+        let price = U256::one() << 96;
+        // encodePriceSqrt(101, 100)
+        let price_target = u256("79623317895830914510639640423");
+        let (next, amount_in, amount_out, fee_amount) =
+            compute_swap_step(price, price_target, 2 * E18 as u128, E18, 600).unwrap();
+        assert_eq!(amount_in, U256::from(9975124224178055u128));
+        assert_eq!(fee_amount, U256::from(5988667735148u128));
+        assert_eq!(amount_out, U256::from(9925619580021728u128));
+        assert_eq!(next, price_target);
+    }
+
+    #[test]
+    fn exact_amount_out_capped_at_price_target() {
+        let price = U256::one() << 96;
+        let price_target = u256("79623317895830914510639640423");
+        let (next, amount_in, amount_out, fee_amount) =
+            compute_swap_step(price, price_target, 2 * E18 as u128, -E18, 600).unwrap();
+        assert_eq!(amount_in, U256::from(9975124224178055u128));
+        assert_eq!(fee_amount, U256::from(5988667735148u128));
+        assert_eq!(amount_out, U256::from(9925619580021728u128));
+        assert_eq!(next, price_target);
+    }
+
+    #[test]
+    fn exact_amount_in_fully_spent() {
+        let price = U256::one() << 96;
+        // encodePriceSqrt(1000, 100)
+        let price_target = u256("250541448375047931186413801569");
+        let (next, amount_in, amount_out, fee_amount) =
+            compute_swap_step(price, price_target, 2 * E18 as u128, E18, 600).unwrap();
+        assert_eq!(amount_in, U256::from(999400000000000000u128));
+        assert_eq!(fee_amount, U256::from(600000000000000u128));
+        assert_eq!(amount_out, U256::from(666399946655997866u128));
+        assert!(next < price_target);
+    }
+
+    #[test]
+    fn exact_amount_out_fully_received() {
+        let price = U256::one() << 96;
+        let price_target = u256("250541448375047931186413801569");
+        let (next, amount_in, amount_out, fee_amount) =
+            compute_swap_step(price, price_target, 2 * E18 as u128, -E18, 600).unwrap();
+        assert_eq!(amount_in, U256::from(2000000000000000000u128));
+        assert_eq!(fee_amount, U256::from(1200720432259356u128));
+        assert_eq!(amount_out, U256::from(E18 as u128));
+        assert!(next < price_target);
+    }
+
+    #[test]
+    fn amount_out_capped_at_the_desired_amount_out() {
+        let (next, amount_in, amount_out, fee_amount) = compute_swap_step(
+            u256("417332158212080721273783715441582"),
+            u256("1452870262520218020823638996"),
+            159344665391607089467575320103,
+            -1,
+            1,
+        )
+        .unwrap();
+        assert_eq!(amount_in, U256::one());
+        assert_eq!(fee_amount, U256::one());
+        assert_eq!(amount_out, U256::one());
+        assert_eq!(next, u256("417332158212080721273783715441581"));
+    }
+
+    #[test]
+    fn entire_input_amount_taken_as_fee() {
+        let (next, amount_in, amount_out, fee_amount) = compute_swap_step(
+            U256::from(2413),
+            u256("79887613182836312"),
+            1985041575832132834610021537970,
+            10,
+            1872,
+        )
+        .unwrap();
+        assert_eq!(amount_in, U256::zero());
+        assert_eq!(fee_amount, U256::from(10));
+        assert_eq!(amount_out, U256::zero());
+        assert_eq!(next, U256::from(2413));
+    }
+}
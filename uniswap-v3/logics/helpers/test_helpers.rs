@@ -0,0 +1,6 @@
+use primitive_types::U256;
+
+/// Parses a decimal value, as the reference values of the Uniswap test suites are written
+pub fn u256(value: &str) -> U256 {
+    U256::from_dec_str(value).unwrap()
+}
@@ -0,0 +1,319 @@
+use crate::{
+    ensure,
+    helpers::liquidity_helper::{add_delta, LiquidityHelperError},
+    helpers::math::WrappingMath,
+    helpers::tick_math::{MAX_TICK, MIN_TICK},
+    impls::pool::data_struct::TickInfo,
+};
+use openbrush::storage::Mapping;
+use primitive_types::U256;
+
+// refer https://github.com/Uniswap/v3-core/blob/main/contracts/libraries/Tick.sol
+// Contains functions for managing tick processes and relevant calculations.
+
+/// Derives max liquidity per tick from given tick spacing
+///
+/// Executed within the pool constructor. Returns the max liquidity per tick.
+pub fn tick_spacing_to_max_liquidity_per_tick(tick_spacing: i32) -> u128 {
+    let min_tick = (MIN_TICK / tick_spacing) * tick_spacing;
+    let max_tick = (MAX_TICK / tick_spacing) * tick_spacing;
+    let num_ticks = ((max_tick - min_tick) / tick_spacing) as u32 + 1;
+    u128::MAX / num_ticks as u128
+}
+
+/// Retrieves fee growth data
+///
+/// Returns the all-time fee growth in token0 and token1, per unit of liquidity,
+/// inside the position's tick boundaries
+pub fn get_fee_growth_inside(
+    ticks: &Mapping<i32, TickInfo>,
+    tick_lower: i32,
+    tick_upper: i32,
+    tick_current: i32,
+    fee_growth_global_0x128: U256,
+    fee_growth_global_1x128: U256,
+) -> (U256, U256) {
+    let lower = ticks.get(&tick_lower).unwrap_or_default();
+    let upper = ticks.get(&tick_upper).unwrap_or_default();
+    let lower_outside_0x128 = U256(lower.fee_growth_outside_0x128);
+    let lower_outside_1x128 = U256(lower.fee_growth_outside_1x128);
+    let upper_outside_0x128 = U256(upper.fee_growth_outside_0x128);
+    let upper_outside_1x128 = U256(upper.fee_growth_outside_1x128);
+
+    // calculate fee growth below
+    let (fee_growth_below_0x128, fee_growth_below_1x128) = if tick_current >= tick_lower {
+        (lower_outside_0x128, lower_outside_1x128)
+    } else {
+        (
+            fee_growth_global_0x128.wrapping_sub(lower_outside_0x128),
+            fee_growth_global_1x128.wrapping_sub(lower_outside_1x128),
+        )
+    };
+
+    // calculate fee growth above
+    let (fee_growth_above_0x128, fee_growth_above_1x128) = if tick_current < tick_upper {
+        (upper_outside_0x128, upper_outside_1x128)
+    } else {
+        (
+            fee_growth_global_0x128.wrapping_sub(upper_outside_0x128),
+            fee_growth_global_1x128.wrapping_sub(upper_outside_1x128),
+        )
+    };
+
+    (
+        fee_growth_global_0x128
+            .wrapping_sub(fee_growth_below_0x128)
+            .wrapping_sub(fee_growth_above_0x128),
+        fee_growth_global_1x128
+            .wrapping_sub(fee_growth_below_1x128)
+            .wrapping_sub(fee_growth_above_1x128),
+    )
+}
+
+/// Updates a tick and returns true if the tick was flipped from initialized to uninitialized, or vice versa
+///
+/// `upper` is true for updating a position's upper tick, or false for updating a position's lower tick.
+/// Fails if the gross liquidity of the tick would exceed `max_liquidity`.
+#[allow(clippy::too_many_arguments)]
+pub fn update(
+    ticks: &mut Mapping<i32, TickInfo>,
+    tick: i32,
+    tick_current: i32,
+    liquidity_delta: i128,
+    fee_growth_global_0x128: U256,
+    fee_growth_global_1x128: U256,
+    seconds_per_liquidity_cumulative_x128: U256,
+    tick_cumulative: i64,
+    time: u32,
+    upper: bool,
+    max_liquidity: u128,
+) -> Result<bool, TickHelperError> {
+    let mut info = ticks.get(&tick).unwrap_or_default();
+
+    let liquidity_gross_before = info.liquidity_gross;
+    let liquidity_gross_after = add_delta(liquidity_gross_before, liquidity_delta)?;
+
+    ensure!(
+        liquidity_gross_after <= max_liquidity,
+        TickHelperError::LiquidityOverflow
+    );
+
+    let flipped = (liquidity_gross_after == 0) != (liquidity_gross_before == 0);
+
+    if liquidity_gross_before == 0 {
+        // by convention, we assume that all growth before a tick was initialized happened _below_ the tick
+        if tick <= tick_current {
+            info.fee_growth_outside_0x128 = fee_growth_global_0x128.0;
+            info.fee_growth_outside_1x128 = fee_growth_global_1x128.0;
+            info.seconds_per_liquidity_outside_x128 = seconds_per_liquidity_cumulative_x128.0;
+            info.tick_cumulative_outside = tick_cumulative;
+            info.seconds_outside = time;
+        }
+        info.initialized = true;
+    }
+
+    info.liquidity_gross = liquidity_gross_after;
+
+    // when the lower (upper) tick is crossed left to right (right to left), liquidity must be added (removed)
+    info.liquidity_net = if upper {
+        info.liquidity_net.checked_sub(liquidity_delta)
+    } else {
+        info.liquidity_net.checked_add(liquidity_delta)
+    }
+    .ok_or(TickHelperError::LiquidityNetOverflow)?;
+
+    ticks.insert(&tick, &info);
+    Ok(flipped)
+}
+
+/// Clears tick data
+#[inline]
+pub fn clear(ticks: &mut Mapping<i32, TickInfo>, tick: i32) {
+    ticks.remove(&tick);
+}
+
+/// Transitions to next tick as needed by price movement
+///
+/// Returns the amount of liquidity added (subtracted) when tick is crossed from left to right (right to left)
+pub fn cross(
+    ticks: &mut Mapping<i32, TickInfo>,
+    tick: i32,
+    fee_growth_global_0x128: U256,
+    fee_growth_global_1x128: U256,
+    seconds_per_liquidity_cumulative_x128: U256,
+    tick_cumulative: i64,
+    time: u32,
+) -> i128 {
+    let mut info = ticks.get(&tick).unwrap_or_default();
+    info.fee_growth_outside_0x128 = fee_growth_global_0x128
+        .wrapping_sub(U256(info.fee_growth_outside_0x128))
+        .0;
+    info.fee_growth_outside_1x128 = fee_growth_global_1x128
+        .wrapping_sub(U256(info.fee_growth_outside_1x128))
+        .0;
+    info.seconds_per_liquidity_outside_x128 = seconds_per_liquidity_cumulative_x128
+        .wrapping_sub(U256(info.seconds_per_liquidity_outside_x128))
+        .0;
+    info.tick_cumulative_outside = tick_cumulative.wrapping_sub(info.tick_cumulative_outside);
+    info.seconds_outside = time.wrapping_sub(info.seconds_outside);
+    ticks.insert(&tick, &info);
+    info.liquidity_net
+}
+
+#[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub enum TickHelperError {
+    LiquidityOverflow,
+    LiquidityNetOverflow,
+    LiquidityHelperError(LiquidityHelperError),
+}
+
+impl From<LiquidityHelperError> for TickHelperError {
+    fn from(error: LiquidityHelperError) -> Self {
+        TickHelperError::LiquidityHelperError(error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn outside(fee_growth_outside_0x128: U256, fee_growth_outside_1x128: U256) -> TickInfo {
+        TickInfo {
+            fee_growth_outside_0x128: fee_growth_outside_0x128.0,
+            fee_growth_outside_1x128: fee_growth_outside_1x128.0,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn max_liquidity_per_tick_of_the_default_fee_tiers() {
+        assert_eq!(
+            tick_spacing_to_max_liquidity_per_tick(10),
+            1917569901783203986719870431555990
+        );
+        assert_eq!(
+            tick_spacing_to_max_liquidity_per_tick(60),
+            11505743598341114571880798222544994
+        );
+        assert_eq!(
+            tick_spacing_to_max_liquidity_per_tick(200),
+            38350317471085141830651933667504588
+        );
+    }
+
+    #[ink_lang::test]
+    fn fee_growth_inside_of_uninitialized_ticks() {
+        let ticks = Mapping::default();
+        let global = U256::from(15);
+        assert_eq!(
+            get_fee_growth_inside(&ticks, -2, 2, 0, global, global),
+            (global, global)
+        );
+        assert_eq!(
+            get_fee_growth_inside(&ticks, -2, 2, 4, global, global),
+            (U256::zero(), U256::zero())
+        );
+        assert_eq!(
+            get_fee_growth_inside(&ticks, -2, 2, -4, global, global),
+            (U256::zero(), U256::zero())
+        );
+    }
+
+    #[ink_lang::test]
+    fn fee_growth_inside_subtracts_upper_and_lower_tick() {
+        let mut ticks = Mapping::default();
+        ticks.insert(&-2, &outside(U256::from(2), U256::from(3)));
+        ticks.insert(&2, &outside(U256::from(4), U256::from(1)));
+        let global = U256::from(15);
+        assert_eq!(
+            get_fee_growth_inside(&ticks, -2, 2, 0, global, global),
+            (U256::from(9), U256::from(11))
+        );
+    }
+
+    #[ink_lang::test]
+    fn fee_growth_inside_wraps_at_256_bits() {
+        let mut ticks = Mapping::default();
+        ticks.insert(&-2, &outside(U256::MAX - 3, U256::MAX - 2));
+        ticks.insert(&2, &outside(U256::from(3), U256::from(5)));
+        let global = U256::from(15);
+        assert_eq!(
+            get_fee_growth_inside(&ticks, -2, 2, 0, global, global),
+            (U256::from(16), U256::from(13))
+        );
+    }
+
+    #[ink_lang::test]
+    fn update_flips_and_caps_the_gross_liquidity() {
+        let mut ticks = Mapping::default();
+        let zero = U256::zero();
+        let (fee_0, fee_1, seconds) = (U256::from(15), U256::from(16), U256::from(17));
+        assert_eq!(
+            update(&mut ticks, 0, 0, 1, fee_0, fee_1, seconds, 18, 19, false, 3),
+            Ok(true)
+        );
+        assert_eq!(
+            update(&mut ticks, 0, 0, 1, zero, zero, zero, 0, 0, false, 3),
+            Ok(false)
+        );
+        assert_eq!(
+            update(&mut ticks, 0, 0, 2, zero, zero, zero, 0, 0, false, 3),
+            Err(TickHelperError::LiquidityOverflow)
+        );
+        // all growth before the tick is initialized happened below it
+        let info = ticks.get(&0).unwrap();
+        assert_eq!((info.liquidity_gross, info.liquidity_net), (2, 2));
+        assert_eq!(U256(info.fee_growth_outside_0x128), fee_0);
+        assert_eq!(U256(info.fee_growth_outside_1x128), fee_1);
+        assert_eq!(U256(info.seconds_per_liquidity_outside_x128), seconds);
+        assert_eq!(
+            (info.tick_cumulative_outside, info.seconds_outside),
+            (18, 19)
+        );
+        assert_eq!(
+            update(&mut ticks, 0, 0, -2, zero, zero, zero, 0, 0, true, 3),
+            Ok(true)
+        );
+        assert_eq!(ticks.get(&0).unwrap().liquidity_net, 4);
+        // ticks above the current tick start without growth
+        update(&mut ticks, 2, 1, 1, fee_0, fee_1, seconds, 18, 19, true, 3).unwrap();
+        let info = ticks.get(&2).unwrap();
+        assert_eq!(U256(info.fee_growth_outside_0x128), zero);
+        assert_eq!(U256(info.seconds_per_liquidity_outside_x128), zero);
+    }
+
+    #[ink_lang::test]
+    fn cross_flips_the_growth_outside() {
+        let mut ticks = Mapping::default();
+        ticks.insert(
+            &2,
+            &TickInfo {
+                liquidity_gross: 3,
+                liquidity_net: 4,
+                seconds_per_liquidity_outside_x128: U256::from(5).0,
+                tick_cumulative_outside: 6,
+                seconds_outside: 7,
+                initialized: true,
+                ..outside(U256::from(1), U256::from(2))
+            },
+        );
+        let liquidity_net = cross(
+            &mut ticks,
+            2,
+            U256::from(7),
+            U256::from(9),
+            U256::from(8),
+            15,
+            10,
+        );
+        assert_eq!(liquidity_net, 4);
+        let info = ticks.get(&2).unwrap();
+        assert_eq!(U256(info.fee_growth_outside_0x128), U256::from(6));
+        assert_eq!(U256(info.fee_growth_outside_1x128), U256::from(7));
+        assert_eq!(U256(info.seconds_per_liquidity_outside_x128), U256::from(3));
+        assert_eq!((info.tick_cumulative_outside, info.seconds_outside), (9, 3));
+        clear(&mut ticks, 2);
+        assert!(ticks.get(&2).is_none());
+    }
+}
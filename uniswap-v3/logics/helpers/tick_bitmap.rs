@@ -0,0 +1,186 @@
+use crate::{
+    ensure,
+    helpers::bit_math::{least_significant_bit, most_significant_bit, BitMathError},
+};
+use openbrush::storage::Mapping;
+use primitive_types::U256;
+
+// refer https://github.com/Uniswap/v3-core/blob/main/contracts/libraries/TickBitmap.sol
+// Stores a packed mapping of tick index to its initialized state.
+// The mapping uses i16 for keys since ticks are represented as i32 and there are 256 (2^8) values per word.
+// U256 has no storage layout, so every word is kept as its little-endian u64 limbs.
+
+/// Computes the position in the mapping where the initialized bit for a tick lives
+///
+/// Returns the key in the mapping containing the word in which the bit is stored
+/// and the bit position in the word where the flag is stored
+#[inline]
+pub fn position(tick: i32) -> (i16, u8) {
+    ((tick >> 8) as i16, (tick & 0xff) as u8)
+}
+
+/// Returns the 256-bit word stored at `word_pos`, zero if nothing was ever written there
+#[inline]
+pub fn word(tick_bitmap: &Mapping<i16, [u64; 4]>, word_pos: i16) -> U256 {
+    U256(tick_bitmap.get(&word_pos).unwrap_or_default())
+}
+
+/// Flips the initialized state for a given tick from false to true, or vice versa
+pub fn flip_tick(
+    tick_bitmap: &mut Mapping<i16, [u64; 4]>,
+    tick: i32,
+    tick_spacing: i32,
+) -> Result<(), TickBitmapError> {
+    // ensure that the tick is spaced
+    ensure!(tick % tick_spacing == 0, TickBitmapError::TickNotSpaced);
+    let (word_pos, bit_pos) = position(tick / tick_spacing);
+    let mask = U256::one() << bit_pos;
+    let flipped = word(tick_bitmap, word_pos) ^ mask;
+    tick_bitmap.insert(&word_pos, &flipped.0);
+    Ok(())
+}
+
+/// Returns the next initialized tick contained in the same word (or adjacent word) as the tick that is either
+/// to the left (less than or equal to) or right (greater than) of the given tick
+///
+/// Returns the next initialized or uninitialized tick up to 256 ticks away from the current tick
+/// and whether the next tick is initialized, as the function only searches within up to 256 ticks
+pub fn next_initialized_tick_within_one_word(
+    tick_bitmap: &Mapping<i16, [u64; 4]>,
+    tick: i32,
+    tick_spacing: i32,
+    lte: bool,
+) -> Result<(i32, bool), TickBitmapError> {
+    let mut compressed = tick / tick_spacing;
+    // round towards negative infinity
+    if tick < 0 && tick % tick_spacing != 0 {
+        compressed -= 1;
+    }
+
+    if lte {
+        let (word_pos, bit_pos) = position(compressed);
+        // all the 1s at or to the right of the current bit_pos
+        let mask = (U256::one() << bit_pos) - 1 + (U256::one() << bit_pos);
+        let masked = word(tick_bitmap, word_pos) & mask;
+
+        // if there are no initialized ticks to the right of or at the current tick, return rightmost in the word
+        let initialized = !masked.is_zero();
+        // overflow/underflow is possible, but prevented externally by limiting both tick_spacing and tick
+        let next = if initialized {
+            (compressed - (bit_pos - most_significant_bit(masked)?) as i32) * tick_spacing
+        } else {
+            (compressed - bit_pos as i32) * tick_spacing
+        };
+        Ok((next, initialized))
+    } else {
+        // start from the word of the next tick, since the current tick state doesn't matter
+        let (word_pos, bit_pos) = position(compressed + 1);
+        // all the 1s at or to the left of the bit_pos
+        let mask = !((U256::one() << bit_pos) - 1);
+        let masked = word(tick_bitmap, word_pos) & mask;
+
+        // if there are no initialized ticks to the left of the current tick, return leftmost in the word
+        let initialized = !masked.is_zero();
+        // overflow/underflow is possible, but prevented externally by limiting both tick_spacing and tick
+        let next = if initialized {
+            (compressed + 1 + (least_significant_bit(masked)? - bit_pos) as i32) * tick_spacing
+        } else {
+            (compressed + 1 + (u8::MAX - bit_pos) as i32) * tick_spacing
+        };
+        Ok((next, initialized))
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub enum TickBitmapError {
+    TickNotSpaced,
+    BitMathError(BitMathError),
+}
+
+impl From<BitMathError> for TickBitmapError {
+    fn from(error: BitMathError) -> Self {
+        TickBitmapError::BitMathError(error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // every test runs against its own storage, the bitmap of Uniswap's TickBitmap spec
+    fn init_ticks() -> Mapping<i16, [u64; 4]> {
+        let mut tick_bitmap = Mapping::default();
+        for tick in [-200, -55, -4, 70, 78, 84, 139, 240, 535] {
+            flip_tick(&mut tick_bitmap, tick, 1).unwrap();
+        }
+        tick_bitmap
+    }
+
+    #[ink_lang::test]
+    fn flip_tick_toggles_only_the_given_tick() {
+        let mut tick_bitmap = Mapping::default();
+        flip_tick(&mut tick_bitmap, -230, 1).unwrap();
+        assert_eq!(
+            next_initialized_tick_within_one_word(&tick_bitmap, -230, 1, true),
+            Ok((-230, true))
+        );
+        assert_eq!(
+            next_initialized_tick_within_one_word(&tick_bitmap, -231, 1, true),
+            Ok((-256, false))
+        );
+        flip_tick(&mut tick_bitmap, -230, 1).unwrap();
+        assert_eq!(
+            next_initialized_tick_within_one_word(&tick_bitmap, -230, 1, true),
+            Ok((-256, false))
+        );
+        assert_eq!(
+            flip_tick(&mut tick_bitmap, 5, 10),
+            Err(TickBitmapError::TickNotSpaced)
+        );
+    }
+
+    #[ink_lang::test]
+    fn next_initialized_tick_to_the_right() {
+        let tick_bitmap = init_ticks();
+        let next = |tick| next_initialized_tick_within_one_word(&tick_bitmap, tick, 1, false);
+        assert_eq!(next(78), Ok((84, true)));
+        assert_eq!(next(-55), Ok((-4, true)));
+        assert_eq!(next(77), Ok((78, true)));
+        assert_eq!(next(-56), Ok((-55, true)));
+        // word boundaries
+        assert_eq!(next(255), Ok((511, false)));
+        assert_eq!(next(-257), Ok((-200, true)));
+        assert_eq!(next(508), Ok((511, false)));
+        assert_eq!(next(383), Ok((511, false)));
+    }
+
+    #[ink_lang::test]
+    fn next_initialized_tick_to_the_left() {
+        let tick_bitmap = init_ticks();
+        let next = |tick| next_initialized_tick_within_one_word(&tick_bitmap, tick, 1, true);
+        assert_eq!(next(78), Ok((78, true)));
+        assert_eq!(next(79), Ok((78, true)));
+        assert_eq!(next(72), Ok((70, true)));
+        // word boundaries
+        assert_eq!(next(258), Ok((256, false)));
+        assert_eq!(next(256), Ok((256, false)));
+        assert_eq!(next(-257), Ok((-512, false)));
+        assert_eq!(next(1023), Ok((768, false)));
+        assert_eq!(next(900), Ok((768, false)));
+    }
+
+    #[ink_lang::test]
+    fn next_initialized_tick_rounds_negative_ticks_down() {
+        let mut tick_bitmap = Mapping::default();
+        flip_tick(&mut tick_bitmap, -230, 10).unwrap();
+        assert_eq!(
+            next_initialized_tick_within_one_word(&tick_bitmap, -225, 10, true),
+            Ok((-230, true))
+        );
+        assert_eq!(
+            next_initialized_tick_within_one_word(&tick_bitmap, -235, 10, false),
+            Ok((-230, true))
+        );
+    }
+}
@@ -0,0 +1,222 @@
+use crate::ensure;
+use primitive_types::U256;
+
+// refer https://github.com/Uniswap/v3-core/blob/main/contracts/libraries/TickMath.sol
+
+/// The minimum tick that may be passed to `get_sqrt_ratio_at_tick`, computed from log base 1.0001 of 2**-128
+pub const MIN_TICK: i32 = -887272;
+/// The maximum tick that may be passed to `get_sqrt_ratio_at_tick`, computed from log base 1.0001 of 2**128
+pub const MAX_TICK: i32 = -MIN_TICK;
+
+/// The minimum value that can be returned from `get_sqrt_ratio_at_tick`, equivalent to get_sqrt_ratio_at_tick(MIN_TICK)
+pub const MIN_SQRT_RATIO: U256 = U256([4295128739, 0, 0, 0]);
+/// The maximum value that can be returned from `get_sqrt_ratio_at_tick`, equivalent to get_sqrt_ratio_at_tick(MAX_TICK)
+/// i.e. 1461446703485210103287273052203988822378723970342
+pub const MAX_SQRT_RATIO: U256 = U256([0x5d951d5263988d26, 0xefd1fc6a50648849, 0xfffd8963, 0]);
+
+// 1/sqrt(1.0001)^(2^i) as Q128.128 for i in 1..20, the i = 0 factor is the initial ratio
+const RATIO_FACTORS: [(u32, u128); 19] = [
+    (0x2, 0xfff97272373d413259a46990580e213a),
+    (0x4, 0xfff2e50f5f656932ef12357cf3c7fdcc),
+    (0x8, 0xffe5caca7e10e4e61c3624eaa0941cd0),
+    (0x10, 0xffcb9843d60f6159c9db58835c926644),
+    (0x20, 0xff973b41fa98c081472e6896dfb254c0),
+    (0x40, 0xff2ea16466c96a3843ec78b326b52861),
+    (0x80, 0xfe5dee046a99a2a811c461f1969c3053),
+    (0x100, 0xfcbe86c7900a88aedcffc83b479aa3a4),
+    (0x200, 0xf987a7253ac413176f2b074cf7815e54),
+    (0x400, 0xf3392b0822b70005940c7a398e4b70f3),
+    (0x800, 0xe7159475a2c29b7443b29c7fa6e889d9),
+    (0x1000, 0xd097f3bdfd2022b8845ad8f792aa5825),
+    (0x2000, 0xa9f746462d870fdf8a65dc1f90e061e5),
+    (0x4000, 0x70d869a156d2a1b890bb3df62baf32f7),
+    (0x8000, 0x31be135f97d08fd981231505542fcfa6),
+    (0x10000, 0x9aa508b5b7a84e1c677de54f3e99bc9),
+    (0x20000, 0x5d6af8dedb81196699c329225ee604),
+    (0x40000, 0x2216e584f5fa1ea926041bedfe98),
+    (0x80000, 0x48a170391f7dc42444e8fa2),
+];
+
+/// Calculates sqrt(1.0001^tick) * 2^96
+///
+/// Returns a Q64.96 number representing the sqrt of the ratio of the two assets (token1/token0)
+/// at the given tick.
+pub fn get_sqrt_ratio_at_tick(tick: i32) -> Result<U256, TickMathError> {
+    let abs_tick = tick.unsigned_abs();
+    ensure!(abs_tick <= MAX_TICK as u32, TickMathError::TickOutOfBounds);
+
+    let mut ratio = if abs_tick & 0x1 != 0 {
+        U256::from(0xfffcb933bd6fad37aa2d162d1a594001u128)
+    } else {
+        U256::one() << 128
+    };
+    for (mask, factor) in RATIO_FACTORS {
+        if abs_tick & mask != 0 {
+            ratio = (ratio * U256::from(factor)) >> 128;
+        }
+    }
+
+    if tick > 0 {
+        ratio = U256::MAX / ratio;
+    }
+
+    // this divides by 1<<32 rounding up to go from a Q128.128 to a Q128.96.
+    // we then downcast because we know the result always fits within 160 bits due to our tick input constraint
+    // we round up in the division so get_tick_at_sqrt_ratio of the output price is always consistent
+    let round_up = if (ratio & U256::from(u32::MAX)).is_zero() {
+        U256::zero()
+    } else {
+        U256::one()
+    };
+    Ok((ratio >> 32) + round_up)
+}
+
+/// Calculates the greatest tick value such that get_sqrt_ratio_at_tick(tick) <= sqrt_price_x96
+///
+/// Throws in case sqrt_price_x96 < MIN_SQRT_RATIO, as MIN_SQRT_RATIO is the lowest value
+/// get_sqrt_ratio_at_tick may ever return.
+pub fn get_tick_at_sqrt_ratio(sqrt_price_x96: U256) -> Result<i32, TickMathError> {
+    // second inequality must be < because the price can never reach the price at the max tick
+    ensure!(
+        sqrt_price_x96 >= MIN_SQRT_RATIO && sqrt_price_x96 < MAX_SQRT_RATIO,
+        TickMathError::SqrtRatioOutOfBounds
+    );
+    let ratio = sqrt_price_x96 << 32;
+
+    let msb = ratio.bits() - 1;
+    let mut r = if msb >= 128 {
+        ratio >> (msb - 127)
+    } else {
+        ratio << (127 - msb)
+    };
+
+    // log_2 is a signed Q64.64, it always fits in an i128
+    let mut log_2: i128 = (msb as i128 - 128) << 64;
+    for shift in (50..=63).rev() {
+        r = (r * r) >> 127;
+        let f = r >> 128;
+        log_2 |= (f.low_u128() as i128) << shift;
+        r >>= f.low_u32();
+    }
+
+    // the remaining math needs a signed 256-bit integer, so it is done in two's complement on U256
+    let log_sqrt10001 = to_twos_complement(log_2)
+        .overflowing_mul(U256::from(255738958999603826347141u128))
+        .0; // 128.128 number
+
+    let tick_low = sar_128_to_i32(
+        log_sqrt10001
+            .overflowing_sub(U256::from(3402992956809132418596140100660247210u128))
+            .0,
+    );
+    let tick_hi = sar_128_to_i32(
+        log_sqrt10001
+            .overflowing_add(U256::from(291339464771989622907027621153398088495u128))
+            .0,
+    );
+
+    if tick_low == tick_hi {
+        Ok(tick_low)
+    } else if get_sqrt_ratio_at_tick(tick_hi)? <= sqrt_price_x96 {
+        Ok(tick_hi)
+    } else {
+        Ok(tick_low)
+    }
+}
+
+#[inline]
+fn to_twos_complement(x: i128) -> U256 {
+    if x < 0 {
+        U256::zero().overflowing_sub(U256::from(x.unsigned_abs())).0
+    } else {
+        U256::from(x as u128)
+    }
+}
+
+/// Arithmetic shift right by 128 of a two's complement value, truncated to the tick's width.
+#[inline]
+fn sar_128_to_i32(x: U256) -> i32 {
+    let shifted = if x.bit(255) { !((!x) >> 128) } else { x >> 128 };
+    shifted.low_u32() as i32
+}
+
+#[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub enum TickMathError {
+    TickOutOfBounds,
+    SqrtRatioOutOfBounds,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::test_helpers::u256;
+
+    #[test]
+    fn get_sqrt_ratio_at_tick_bounds() {
+        assert_eq!(
+            get_sqrt_ratio_at_tick(MIN_TICK - 1),
+            Err(TickMathError::TickOutOfBounds)
+        );
+        assert_eq!(
+            get_sqrt_ratio_at_tick(MAX_TICK + 1),
+            Err(TickMathError::TickOutOfBounds)
+        );
+        assert_eq!(get_sqrt_ratio_at_tick(MIN_TICK), Ok(MIN_SQRT_RATIO));
+        assert_eq!(
+            get_sqrt_ratio_at_tick(MIN_TICK + 1),
+            Ok(U256::from(4295343490u64))
+        );
+        assert_eq!(
+            get_sqrt_ratio_at_tick(MAX_TICK - 1),
+            Ok(u256("1461373636630004318706518188784493106690254656249"))
+        );
+        assert_eq!(get_sqrt_ratio_at_tick(MAX_TICK), Ok(MAX_SQRT_RATIO));
+        assert_eq!(
+            MAX_SQRT_RATIO,
+            u256("1461446703485210103287273052203988822378723970342")
+        );
+    }
+
+    #[test]
+    fn get_sqrt_ratio_at_tick_values() {
+        assert_eq!(get_sqrt_ratio_at_tick(0), Ok(U256::one() << 96));
+        assert_eq!(
+            get_sqrt_ratio_at_tick(50),
+            Ok(u256("79426470787362580746886972461"))
+        );
+    }
+
+    #[test]
+    fn get_tick_at_sqrt_ratio_bounds() {
+        assert_eq!(
+            get_tick_at_sqrt_ratio(MIN_SQRT_RATIO - 1),
+            Err(TickMathError::SqrtRatioOutOfBounds)
+        );
+        assert_eq!(
+            get_tick_at_sqrt_ratio(MAX_SQRT_RATIO),
+            Err(TickMathError::SqrtRatioOutOfBounds)
+        );
+        assert_eq!(get_tick_at_sqrt_ratio(MIN_SQRT_RATIO), Ok(MIN_TICK));
+        assert_eq!(
+            get_tick_at_sqrt_ratio(U256::from(4295343490u64)),
+            Ok(MIN_TICK + 1)
+        );
+        assert_eq!(
+            get_tick_at_sqrt_ratio(u256("1461373636630004318706518188784493106690254656249")),
+            Ok(MAX_TICK - 1)
+        );
+        assert_eq!(get_tick_at_sqrt_ratio(MAX_SQRT_RATIO - 1), Ok(MAX_TICK - 1));
+    }
+
+    #[test]
+    fn get_tick_at_sqrt_ratio_is_the_greatest_tick_at_or_below_the_ratio() {
+        let mut tick = MIN_TICK + 1;
+        while tick < MAX_TICK {
+            let ratio = get_sqrt_ratio_at_tick(tick).unwrap();
+            assert_eq!(get_tick_at_sqrt_ratio(ratio), Ok(tick));
+            assert_eq!(get_tick_at_sqrt_ratio(ratio - 1), Ok(tick - 1));
+            tick += 997;
+        }
+    }
+}
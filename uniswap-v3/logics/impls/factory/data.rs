@@ -3,11 +3,28 @@ use openbrush::{storage::Mapping, traits::AccountId};
 
 pub const STORAGE_KEY: u32 = openbrush::storage_unique_key!(Data);
 
+/// Version of the storage layout below, see `Factory::migrate`
+pub const STORAGE_VERSION: u32 = 1;
+
 #[derive(Default, Debug)]
 #[openbrush::upgradeable_storage(STORAGE_KEY)]
 pub struct Data {
+    // see STORAGE_VERSION, first so Factory::migrate finds it whatever the layout below
+    pub version: u32,
     pub owner: AccountId,
+    // set by Factory::propose_owner until the proposed account accepts
+    pub pending_owner: Option<AccountId>,
     pub fee_amount_tick_spacing: Mapping<u32, i32>,
     pub get_pool: Mapping<(AccountId, AccountId, u32), AccountId>,
     pub pool_contract_code_hash: Hash,
+    // append-only registry of every pool created by the factory
+    pub all_pools: Mapping<u64, AccountId>,
+    pub all_pools_length: u64,
+    // token -> index -> pool, see Factory::pools_for_token
+    pub token_pools: Mapping<(AccountId, u64), AccountId>,
+    pub token_pools_length: Mapping<AccountId, u64>,
+    // sorted token pair -> index -> pool, see Factory::pools_for_pair
+    pub pair_pools: Mapping<(AccountId, AccountId, u64), AccountId>,
+    pub pair_pools_length: Mapping<(AccountId, AccountId), u64>,
+    pub is_pool: Mapping<AccountId, bool>,
 }
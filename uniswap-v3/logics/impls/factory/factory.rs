@@ -1,14 +1,30 @@
 pub use crate::{impls::factory::*, traits::factory::*};
 
-use ink_env::hash::Blake2x256;
+use crate::ensure;
+use crate::helpers::{helper::pool_salt, swap_math::FEE_DENOMINATOR};
+use ink_env::Hash;
+use ink_prelude::vec::Vec;
 
 use openbrush::{
-    contracts::ownable::*,
-    modifiers,
+    modifier_definition, modifiers,
     traits::{AccountId, Storage, ZERO_ADDRESS},
 };
 
-impl<T: Storage<data::Data> + Storage<ownable::Data>> Factory for T {
+/// Throws if called by any account other than the factory owner
+#[modifier_definition]
+pub fn only_owner<T, F, R, E>(instance: &mut T, body: F) -> Result<R, E>
+where
+    T: Storage<data::Data>,
+    F: FnOnce(&mut T) -> Result<R, E>,
+    E: From<FactoryError>,
+{
+    if instance.data().owner != T::env().caller() {
+        return Err(From::from(FactoryError::CallerIsNotOwner));
+    }
+    body(instance)
+}
+
+impl<T: Storage<data::Data>> Factory for T {
     fn create_pool(
         &mut self,
         token_a: AccountId,
@@ -32,18 +48,14 @@ impl<T: Storage<data::Data> + Storage<ownable::Data>> Factory for T {
         if tick_spacing == 0 {
             return Err(FactoryError::ZeroTickSpacing);
         }
-        ///////////////////////////////////////////////////////////////////////////////////////
-        // not sure how to translate UniswapV3PoolDeployer.sol and deploy() to ink!
-        // will use a similar solution than the one use for UniswapV2Factory translation to ink!
-
-        let salt = Self::env().hash_encoded::<Blake2x256, _>(&token_pair);
-        let pool_contract = self._instantiate_pool(salt.as_ref())?;
-
-        /////////////////////////////                             uncomment once pool contract is coded                            ///////////////////////////////////////////////////////
-        // PoolRef::initialize(&pool_contract, token_pair.0, token_pair.1, fee)?;
-        /////////////////////////////                                                                                              ///////////////////////////////////////////////////////
-
-        //////////////////////////////////////////////////////////////////////////////////////
+        if self.get_pool(token_pair.0, token_pair.1, fee).is_some() {
+            return Err(FactoryError::PoolAlreadyExists);
+        }
+        // instead of UniswapV3PoolDeployer's transient parameters, the pool receives its immutables
+        // as constructor arguments and records the factory as the caller of its constructor
+        let salt = pool_salt(token_pair.0, token_pair.1, fee);
+        let pool_contract =
+            self._instantiate_pool(&salt, token_pair.0, token_pair.1, fee, tick_spacing)?;
 
         self.data::<data::Data>()
             .get_pool
@@ -51,12 +63,20 @@ impl<T: Storage<data::Data> + Storage<ownable::Data>> Factory for T {
         self.data::<data::Data>()
             .get_pool
             .insert(&(token_pair.1, token_pair.0, fee), &pool_contract);
+        self._register_pool(token_pair.0, token_pair.1, pool_contract);
 
         self._emit_create_pool_event(token_a, token_b, fee, tick_spacing, pool_contract);
         Ok(pool_contract)
     }
 
-    default fn _instantiate_pool(&mut self, _salt_bytes: &[u8]) -> Result<AccountId, FactoryError> {
+    default fn _instantiate_pool(
+        &mut self,
+        _salt_bytes: &[u8],
+        _token_0: AccountId,
+        _token_1: AccountId,
+        _fee: u32,
+        _tick_spacing: i32,
+    ) -> Result<AccountId, FactoryError> {
         unimplemented!()
     }
 
@@ -66,33 +86,190 @@ impl<T: Storage<data::Data> + Storage<ownable::Data>> Factory for T {
             .get(&(token_a, token_b, fee))
     }
 
+    fn all_pools_length(&self) -> u64 {
+        self.data::<data::Data>().all_pools_length
+    }
+
+    fn all_pools(&self, index: u64) -> Option<AccountId> {
+        self.data::<data::Data>().all_pools.get(&index)
+    }
+
+    fn pools_for_token_length(&self, token: AccountId) -> u64 {
+        self.data::<data::Data>()
+            .token_pools_length
+            .get(&token)
+            .unwrap_or_default()
+    }
+
+    fn pools_for_token(&self, token: AccountId, start: u64, limit: u64) -> Vec<AccountId> {
+        let end = start
+            .saturating_add(limit)
+            .min(self.pools_for_token_length(token));
+        (start..end)
+            .filter_map(|index| self.data::<data::Data>().token_pools.get(&(token, index)))
+            .collect()
+    }
+
+    fn pools_for_pair_length(&self, token_a: AccountId, token_b: AccountId) -> u64 {
+        let (token_0, token_1) = if token_a < token_b {
+            (token_a, token_b)
+        } else {
+            (token_b, token_a)
+        };
+        self.data::<data::Data>()
+            .pair_pools_length
+            .get(&(token_0, token_1))
+            .unwrap_or_default()
+    }
+
+    fn pools_for_pair(
+        &self,
+        token_a: AccountId,
+        token_b: AccountId,
+        start: u64,
+        limit: u64,
+    ) -> Vec<AccountId> {
+        let (token_0, token_1) = if token_a < token_b {
+            (token_a, token_b)
+        } else {
+            (token_b, token_a)
+        };
+        let end = start
+            .saturating_add(limit)
+            .min(self.pools_for_pair_length(token_0, token_1));
+        (start..end)
+            .filter_map(|index| {
+                self.data::<data::Data>()
+                    .pair_pools
+                    .get(&(token_0, token_1, index))
+            })
+            .collect()
+    }
+
+    fn is_pool(&self, account: AccountId) -> bool {
+        self.data::<data::Data>()
+            .is_pool
+            .get(&account)
+            .unwrap_or_default()
+    }
+
+    fn pool_code_hash(&self) -> Hash {
+        self.data::<data::Data>().pool_contract_code_hash
+    }
+
+    #[modifiers(only_owner)]
+    fn set_pool_code_hash(&mut self, code_hash: [u8; 32]) -> Result<(), FactoryError> {
+        self.data::<data::Data>().pool_contract_code_hash = code_hash.into();
+        Ok(())
+    }
+
+    #[modifiers(only_owner)]
+    fn set_code_hash(&mut self, code_hash: [u8; 32]) -> Result<(), FactoryError> {
+        ink_env::set_code_hash(&code_hash).map_err(|_| FactoryError::SetCodeHashFailed)
+    }
+
+    fn storage_version(&self) -> u32 {
+        self.data::<data::Data>().version
+    }
+
+    #[modifiers(only_owner)]
+    fn migrate(&mut self) -> Result<(), FactoryError> {
+        let version = self.data::<data::Data>().version;
+        ensure!(
+            version < data::STORAGE_VERSION,
+            FactoryError::StorageUpToDate
+        );
+        self._migrate(version)?;
+        self.data::<data::Data>().version = data::STORAGE_VERSION;
+        Ok(())
+    }
+
+    default fn _migrate(&mut self, _from_version: u32) -> Result<(), FactoryError> {
+        Ok(())
+    }
+
+    default fn _register_pool(&mut self, token_0: AccountId, token_1: AccountId, pool: AccountId) {
+        let index = self.data::<data::Data>().all_pools_length;
+        self.data::<data::Data>().all_pools.insert(&index, &pool);
+        self.data::<data::Data>().all_pools_length = index + 1;
+
+        for token in [token_0, token_1] {
+            let token_index = self
+                .data::<data::Data>()
+                .token_pools_length
+                .get(&token)
+                .unwrap_or_default();
+            self.data::<data::Data>()
+                .token_pools
+                .insert(&(token, token_index), &pool);
+            self.data::<data::Data>()
+                .token_pools_length
+                .insert(&token, &(token_index + 1));
+        }
+
+        let pair_index = self.pools_for_pair_length(token_0, token_1);
+        self.data::<data::Data>()
+            .pair_pools
+            .insert(&(token_0, token_1, pair_index), &pool);
+        self.data::<data::Data>()
+            .pair_pools_length
+            .insert(&(token_0, token_1), &(pair_index + 1));
+
+        self.data::<data::Data>().is_pool.insert(&pool, &true);
+    }
+
+    fn owner(&self) -> AccountId {
+        self.data::<data::Data>().owner
+    }
+
+    fn pending_owner(&self) -> Option<AccountId> {
+        self.data::<data::Data>().pending_owner
+    }
+
     #[modifiers(only_owner)]
-    fn set_owner(&mut self, new_owner: AccountId) -> Result<(), FactoryError> {
+    fn propose_owner(&mut self, new_owner: AccountId) -> Result<(), FactoryError> {
+        self.data::<data::Data>().pending_owner = Some(new_owner);
+        Ok(())
+    }
+
+    fn accept_owner(&mut self) -> Result<(), FactoryError> {
+        let caller = Self::env().caller();
+        if self.data::<data::Data>().pending_owner != Some(caller) {
+            return Err(FactoryError::CallerIsNotPendingOwner);
+        }
         let previous_owner = self.data::<data::Data>().owner;
-        self._emit_owner_changed_event(previous_owner, new_owner);
-        self.data::<data::Data>().owner = new_owner;
+        self.data::<data::Data>().owner = caller;
+        self.data::<data::Data>().pending_owner = None;
+        self._emit_owner_changed_event(previous_owner, caller);
         Ok(())
     }
 
     #[modifiers(only_owner)]
     fn enable_fee_amount(&mut self, fee: u32, tick_spacing: i32) -> Result<(), FactoryError> {
-        if fee < 1000000 {
+        if fee >= FEE_DENOMINATOR {
             return Err(FactoryError::FeeTooBig);
         }
 
+        // tick spacing is capped at 16384 to prevent the situation where tick_spacing is so large that
+        // TickBitmap::next_initialized_tick_within_one_word overflows i32 container from a valid tick
+        // 16384 ticks represents a >5x price change with ticks of 1 bips
         let tick_spacing_range = 1..16384;
         if !tick_spacing_range.contains(&tick_spacing) {
             return Err(FactoryError::TickSpacingOutOfBonds);
         }
 
-        if self.get_fee_amount_tick_spacing(fee).unwrap() != 0 {
+        if self.get_fee_amount_tick_spacing(fee).unwrap_or_default() != 0 {
             return Err(FactoryError::NonZeroTickSpacing);
         }
+        self._enable_fee_amount(fee, tick_spacing);
+        Ok(())
+    }
+
+    default fn _enable_fee_amount(&mut self, fee: u32, tick_spacing: i32) {
         self.data::<data::Data>()
             .fee_amount_tick_spacing
             .insert(&fee, &tick_spacing);
         self._emit_fee_amount_enabled_event(fee, tick_spacing);
-        Ok(())
     }
 
     fn get_fee_amount_tick_spacing(&self, fee: u32) -> Option<i32> {
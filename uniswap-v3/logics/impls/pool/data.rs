@@ -8,9 +8,15 @@ use openbrush::{
 
 pub const STORAGE_KEY: u32 = openbrush::storage_unique_key!(Data);
 
+/// Version of the pool storage layout below, upgraded through `Pool::migrate`
+pub const STORAGE_VERSION: u32 = 1;
+
 #[derive(Default, Debug)]
 #[openbrush::upgradeable_storage(STORAGE_KEY)]
 pub struct Data {
+    // layout version of this storage, see STORAGE_VERSION, kept first so that its position
+    // survives any change to the fields below
+    pub version: u32,
     pub factory: AccountId,
     pub token_0: AccountId,
     pub token_1: AccountId,
@@ -18,17 +24,16 @@ pub struct Data {
     pub tick_spacing: i32,
     pub max_liquidity_per_tick: u128,
     pub slot_0: Slot,
-    pub fee_growth_global_0x128: u128,
-    pub fee_growth_global_1x128: u128,
+    // all-time fee growth per unit of liquidity, as U256 limbs like the tick bitmap words
+    pub fee_growth_global_0x128: [u64; 4],
+    pub fee_growth_global_1x128: [u64; 4],
     pub liquidity: u128,
     pub ticks: Mapping<i32, TickInfo>,
-    pub tick_bitmap: Mapping<i32, u128>,
+    // word position -> 256-bit word, see helpers::tick_bitmap
+    pub tick_bitmap: Mapping<i16, [u64; 4]>,
     pub positions: Mapping<[u8; 32], PositionInfo>,
     pub fee0: Balance,
     pub fee1: Balance,
-    pub swap_cache: SwapCache,
-    pub swap_state: SwapState,
-    pub step_computations: StepComputations,
-    // pub observation_vec: Vec<Observation>,
-    // pub observation_array: [Observation;65535],
+    // ring buffer of oracle observations, see helpers::oracle
+    pub observations: Mapping<u16, Observation>,
 }
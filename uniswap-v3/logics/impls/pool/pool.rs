@@ -1,20 +1,33 @@
 use crate::impls::pool::data_struct::*;
-use ink_env::hash::Blake2x256;
 use ink_prelude::vec::Vec;
 use primitive_types::U256;
 
-use crate::helpers::liquidity_helper::liquidity_num::{
-    MAX_SQRT_RATIO, MAX_TICK, MIN_SQRT_RATIO, MIN_TICK,
-};
-use crate::helpers::liquidity_helper::{
-    _compute_swap_step, add_delta, cross, get_sqrt_ratio_at_tick, get_tick_at_sqrt_ratio, mul_div,
-    next_initialized_tick_within_oneword, observe_single, tick_bitmap, write,
+use crate::helpers::full_math::{mul_div, mul_div_rounding_up};
+use crate::helpers::liquidity_helper::add_delta;
+use crate::helpers::math::{WrappingMath, Q128};
+use crate::helpers::oracle::{self, grow, observe_single, write};
+use crate::helpers::position;
+use crate::helpers::sqrt_price_math::{get_amount0_delta_signed, get_amount1_delta_signed};
+use crate::helpers::swap_math::compute_swap_step;
+use crate::helpers::tick::{self, cross, get_fee_growth_inside};
+use crate::helpers::tick_bitmap::{flip_tick, next_initialized_tick_within_one_word, word};
+use crate::helpers::tick_math::{
+    get_sqrt_ratio_at_tick, get_tick_at_sqrt_ratio, MAX_SQRT_RATIO, MAX_TICK, MIN_SQRT_RATIO,
+    MIN_TICK,
 };
 use crate::{ensure, helpers::transfer_helper::safe_transfer};
-use crate::{impls::pool::*, traits::pool::*};
+use crate::{
+    impls::pool::*,
+    traits::{
+        callback::{FlashCallbackRef, MintCallbackRef, SwapCallbackRef},
+        factory::FactoryRef,
+        pool::*,
+    },
+};
 use openbrush::{
-    contracts::{ownable::*, psp22::*, reentrancy_guard::*, traits::psp22::PSP22Ref},
-    traits::{AccountId, Balance, Storage},
+    contracts::{psp22::*, reentrancy_guard::*, traits::psp22::PSP22Ref},
+    modifier_definition, modifiers,
+    traits::{AccountId, Balance, Flush, Storage},
 };
 
 pub struct ModifyPositionParams {
@@ -28,24 +41,25 @@ pub struct ModifyPositionParams {
 }
 
 pub trait Internal {
-    fn _emit_initialize_event(&self, sqrt_price_x96: u128, tick: i32);
+    fn _emit_initialize_event(&self, sqrt_price_x96: U256, tick: i32);
 
     fn _emit_collect_event(
         &self,
+        owner: AccountId,
         recipient: AccountId,
         tick_lower: i32,
         tick_upper: i32,
-        amount0_requested: Balance,
-        amount1_requested: Balance,
+        amount0: Balance,
+        amount1: Balance,
     );
 
     fn _emit_swap_event(
         &self,
         sender: AccountId,
         recipient: AccountId,
-        amount0: Balance,
-        amount1: Balance,
-        sqrt_price_x96: u128,
+        amount0: i128,
+        amount1: i128,
+        sqrt_price_x96: U256,
         liquidity: u128,
         tick: i32,
     );
@@ -69,22 +83,93 @@ pub trait Internal {
         &self,
         sender: AccountId,
         recipient: AccountId,
-        amount0_requested: Balance,
-        amount1_requested: Balance,
+        amount0: Balance,
+        amount1: Balance,
+    );
+    fn _emit_increase_observation_cardinality_next_event(
+        &self,
+        observation_cardinality_next_old: u16,
+        observation_cardinality_next_new: u16,
     );
 }
-impl<T: Storage<data::Data> + Internal> Pool for T {
-    fn initialize(
-        &mut self,
-        token_0: AccountId,
-        token_1: AccountId,
-        fee: u32,
-    ) -> Result<(), PoolError> {
-        self.data::<data::Data>().token_0 = token_0;
-        self.data::<data::Data>().token_1 = token_1;
-        self.data::<data::Data>().fee = fee;
+
+/// Mutually exclusive reentrancy protection into the pool to/from a message
+///
+/// Also prevents entrance to a message before the pool is initialized. The lock is flushed before running the
+/// body, so callbacks re-entering the pool observe it. Messages flush again right before invoking a callback, and
+/// the state views fail through `_require_unlocked` while the lock is held.
+#[modifier_definition]
+pub fn lock<T, F, R, E>(instance: &mut T, body: F) -> Result<R, E>
+where
+    T: Storage<data::Data> + Flush,
+    F: FnOnce(&mut T) -> Result<R, E>,
+    E: From<PoolError>,
+{
+    if U256(instance.data().slot_0.sqrt_price_x96).is_zero() {
+        return Err(From::from(PoolError::NotInitialized));
+    }
+    if !instance.data().slot_0.unlocked {
+        return Err(From::from(PoolError::PoolIsLocked));
+    }
+    instance.data().slot_0.unlocked = false;
+    instance.flush();
+    let result = body(instance);
+    instance.data().slot_0.unlocked = true;
+    result
+}
+
+/// Throws while a locked message is waiting on a callback, unlike `lock` an uninitialized pool passes
+#[modifier_definition]
+pub fn no_callback<T, F, R, E>(instance: &mut T, body: F) -> Result<R, E>
+where
+    T: Storage<data::Data>,
+    F: FnOnce(&mut T) -> Result<R, E>,
+    E: From<PoolError>,
+{
+    let slot_0 = instance.data().slot_0;
+    if !U256(slot_0.sqrt_price_x96).is_zero() && !slot_0.unlocked {
+        return Err(From::from(PoolError::PoolIsLocked));
+    }
+    body(instance)
+}
+
+/// Throws if the caller is not the owner of the factory that deployed the pool
+#[modifier_definition]
+pub fn only_factory_owner<T, F, R, E>(instance: &mut T, body: F) -> Result<R, E>
+where
+    T: Storage<data::Data>,
+    F: FnOnce(&mut T) -> Result<R, E>,
+    E: From<PoolError>,
+{
+    if FactoryRef::owner(&instance.data().factory) != T::env().caller() {
+        return Err(From::from(PoolError::CallerIsNotFactoryOwner));
+    }
+    body(instance)
+}
+
+impl<T: Storage<data::Data> + Flush + Internal> Pool for T {
+    fn initialize(&mut self, sqrt_price_x96: U256) -> Result<(), PoolError> {
+        ensure!(
+            U256(self.data::<data::Data>().slot_0.sqrt_price_x96).is_zero(),
+            PoolError::AlreadyInitialized
+        );
+        let tick = get_tick_at_sqrt_ratio(sqrt_price_x96)?;
+        let time = self._block_timestamp();
+        let (cardinality, cardinality_next) =
+            oracle::initialize(&mut self.data::<data::Data>().observations, time);
+        self.data::<data::Data>().slot_0 = Slot {
+            sqrt_price_x96: sqrt_price_x96.0,
+            tick,
+            observation_index: 0,
+            observation_cardinality: cardinality,
+            observation_cardinality_next: cardinality_next,
+            fee_protocol: 0,
+            unlocked: true,
+        };
+        self._emit_initialize_event(sqrt_price_x96, tick);
         Ok(())
     }
+    #[modifiers(lock)]
     fn collect(
         &mut self,
         recipient: AccountId,
@@ -93,54 +178,47 @@ impl<T: Storage<data::Data> + Internal> Pool for T {
         amount0_requested: Balance,
         amount1_requested: Balance,
     ) -> Result<(Balance, Balance), PoolError> {
-        let mut amount_0: Balance;
-        let mut amount_1: Balance;
-        let token_0 = self.data::<data::Data>().token_0;
-        let token_1 = self.data::<data::Data>().token_1;
         let caller = Self::env().caller();
-        let mut owned_amount_0 = 0;
-        let mut owned_amount_1 = 0;
-        let position_info = self.get_position(caller, tick_lower, tick_upper);
-        if let Some(info) = position_info {
-            owned_amount_0 = info.tokens_owed_0;
-            owned_amount_1 = info.tokens_owed_1;
-        }
+        // we don't need to check_ticks here, because invalid positions will never have non-zero tokens_owed
+        let mut position = position::get(
+            &self.data::<data::Data>().positions,
+            caller,
+            tick_lower,
+            tick_upper,
+        );
 
-        if amount0_requested > owned_amount_0 {
-            amount_0 = owned_amount_0;
-        } else {
-            amount_0 = amount0_requested;
-        }
-        if amount1_requested > owned_amount_1 {
-            amount_1 = owned_amount_1;
-        } else {
-            amount_1 = amount1_requested;
+        let amount_0 = amount0_requested.min(position.tokens_owed_0);
+        let amount_1 = amount1_requested.min(position.tokens_owed_1);
+
+        if amount_0 > 0 || amount_1 > 0 {
+            position.tokens_owed_0 -= amount_0;
+            position.tokens_owed_1 -= amount_1;
+            self.data::<data::Data>()
+                .positions
+                .insert(&position::key(caller, tick_lower, tick_upper), &position);
         }
         if amount_0 > 0 {
-            owned_amount_0 -= amount_0;
-            safe_transfer(token_0, recipient, amount_0);
-        } else {
-            // ensure!(amount_0 == 0, PoolError);
+            safe_transfer(self.data::<data::Data>().token_0, recipient, amount_0)?;
         }
         if amount_1 > 0 {
-            owned_amount_1 -= amount_1;
-            safe_transfer(token_1, recipient, amount_1);
-        } else {
-            // ensure!(amount_1 == 0, PoolError);
+            safe_transfer(self.data::<data::Data>().token_1, recipient, amount_1)?;
         }
-        self._emit_collect_event(recipient, tick_lower, tick_upper, amount_0, amount_1);
+
+        self._emit_collect_event(
+            caller, recipient, tick_lower, tick_upper, amount_0, amount_1,
+        );
         Ok((amount_0, amount_1))
     }
 
+    #[modifiers(lock)]
     fn swap(
         &mut self,
         recipient: AccountId,
         zero_for_one: bool,
         amount_specified: i128,
-        sqrt_price_limit_x96: u128,
-    ) -> Result<(Balance, Balance), PoolError> {
-        let mut amount_0: Balance;
-        let mut amount_1: Balance;
+        sqrt_price_limit_x96: U256,
+        data: Vec<u8>,
+    ) -> Result<(i128, i128), PoolError> {
         let min_tick = MIN_TICK;
         let max_tick = MAX_TICK;
         let token_0 = self.data::<data::Data>().token_0;
@@ -148,190 +226,184 @@ impl<T: Storage<data::Data> + Internal> Pool for T {
         let min_sqrt_ratio = MIN_SQRT_RATIO;
         let max_sqrt_ratio = MAX_SQRT_RATIO;
         ensure!(amount_specified != 0, PoolError::AmountSpecifiedIsZero);
-        let mut slot0_start = self.data::<data::Data>().slot_0;
-        ensure!(slot0_start.unlocked, PoolError::PoolIsLocked);
+        let slot0_start = self.data::<data::Data>().slot_0;
+        let slot0_start_sqrt_price_x96 = U256(slot0_start.sqrt_price_x96);
         let caller = Self::env().caller();
-        // TODO: implement tick_math
         if zero_for_one {
             ensure!(
-                sqrt_price_limit_x96 < slot0_start.sqrt_price_x96
+                sqrt_price_limit_x96 < slot0_start_sqrt_price_x96
                     && sqrt_price_limit_x96 > min_sqrt_ratio,
                 PoolError::SqrtPriceLimitX96IsInvalid
             );
         } else {
             ensure!(
-                // TODO: FIX TYPE ERROR MAX_SQRT_RATIO
-                sqrt_price_limit_x96 > slot0_start.sqrt_price_x96
+                sqrt_price_limit_x96 > slot0_start_sqrt_price_x96
                     && sqrt_price_limit_x96 < max_sqrt_ratio,
                 PoolError::SqrtPriceLimitX96IsInvalid
             );
         }
-        slot0_start.unlocked = false;
         // refer https://github.com/Uniswap/v3-core/blob/05c10bf6d547d6121622ac51c457f93775e1df09/contracts/UniswapV3Pool.sol#L622
         let mut cache = SwapCache {
             liquidity_start: self.data::<data::Data>().liquidity,
-            block_timestamp: 0,
-            fee_protocol: 0,
-            seconds_per_liquidity_cumulative_x128: 0,
+            block_timestamp: self._block_timestamp(),
+            fee_protocol: if zero_for_one {
+                slot0_start.fee_protocol % 16
+            } else {
+                slot0_start.fee_protocol >> 4
+            },
+            seconds_per_liquidity_cumulative_x128: U256::zero(),
             tick_cumulative: 0,
             computed_latest_observations: false,
         };
 
         let exact_input = amount_specified > 0;
+        let fee = self.data::<data::Data>().fee;
+        let tick_spacing = self.data::<data::Data>().tick_spacing;
         let mut state = SwapState {
             amount_specified_remaining: amount_specified,
             amount_calculated: 0,
-            sqrt_price_x96: slot0_start.sqrt_price_x96,
+            sqrt_price_x96: slot0_start_sqrt_price_x96,
             tick: slot0_start.tick,
-            fee_growth_global_x128: if zero_for_one {
+            fee_growth_global_x128: U256(if zero_for_one {
                 self.data::<data::Data>().fee_growth_global_0x128
             } else {
                 self.data::<data::Data>().fee_growth_global_1x128
-            },
+            }),
             protocol_fee: 0,
             liquidity: cache.liquidity_start,
         };
         // continue swapping as long as we haven't used the entire input/output and haven't reached the price limit
         while state.amount_specified_remaining != 0 && state.sqrt_price_x96 != sqrt_price_limit_x96
         {
-            let _fee = self.data::<data::Data>().fee;
-            let _tick_spacing = self.data::<data::Data>().tick_spacing;
-            let mut step = &mut self.data::<data::Data>().step_computations;
-            step.sqrt_price_start_x96 = state.sqrt_price_x96;
-            // TODO: implement tick_bitmap
-            // TODO: implement next_initialized_tick_within_one_word
-            (step.sqrt_price_next_x96, step.initialized) =
-                next_initialized_tick_within_oneword(state.tick, _tick_spacing, zero_for_one);
+            let mut step = StepComputations {
+                sqrt_price_start_x96: state.sqrt_price_x96,
+                ..Default::default()
+            };
+            (step.tick_next, step.initialized) = next_initialized_tick_within_one_word(
+                &self.data::<data::Data>().tick_bitmap,
+                state.tick,
+                tick_spacing,
+                zero_for_one,
+            )?;
             // ensure that we do not overshoot the min/max tick, as the tick bitmap is not aware of these bounds
             if step.tick_next < min_tick {
                 step.tick_next = min_tick;
             } else if step.tick_next > max_tick {
                 step.tick_next = max_tick;
             }
-            //get the price for the next tick
-            step.sqrt_price_next_x96 = get_sqrt_ratio_at_tick(step.tick_next);
+            // get the price for the next tick
+            step.sqrt_price_next_x96 = get_sqrt_ratio_at_tick(step.tick_next)?;
 
             // compute values to swap to the target tick, price limit, or point where input/output amount is exhausted
-            //  TODO implement _compute_swap_step
-            (
-                state.sqrt_price_x96,
-                step.amount_in,
-                step.amount_out,
-                step.fee_amount,
-            ) = if zero_for_one {
+            let sqrt_price_target_x96 = if zero_for_one {
                 if step.sqrt_price_next_x96 < sqrt_price_limit_x96 {
-                    _compute_swap_step(
-                        state.sqrt_price_x96,
-                        sqrt_price_limit_x96,
-                        state.liquidity,
-                        state.amount_specified_remaining,
-                        _fee,
-                    )
+                    sqrt_price_limit_x96
                 } else {
-                    _compute_swap_step(
-                        state.sqrt_price_x96,
-                        step.sqrt_price_next_x96,
-                        state.liquidity,
-                        state.amount_specified_remaining,
-                        _fee,
-                    )
+                    step.sqrt_price_next_x96
                 }
+            } else if step.sqrt_price_next_x96 > sqrt_price_limit_x96 {
+                sqrt_price_limit_x96
             } else {
-                if step.sqrt_price_next_x96 > sqrt_price_limit_x96 {
-                    _compute_swap_step(
-                        state.sqrt_price_x96,
-                        sqrt_price_limit_x96,
-                        state.liquidity,
-                        state.amount_specified_remaining,
-                        _fee,
-                    )
-                } else {
-                    _compute_swap_step(
-                        state.sqrt_price_x96,
-                        step.sqrt_price_next_x96,
-                        state.liquidity,
-                        state.amount_specified_remaining,
-                        _fee,
-                    )
-                }
+                step.sqrt_price_next_x96
             };
-            // TODO implement converter(openzeppelin)
-            // refer https://github.com/Uniswap/v3-core/blob/05c10bf6d547d6121622ac51c457f93775e1df09/contracts/UniswapV3Pool.sol#L676
+            (
+                state.sqrt_price_x96,
+                step.amount_in,
+                step.amount_out,
+                step.fee_amount,
+            ) = compute_swap_step(
+                state.sqrt_price_x96,
+                sqrt_price_target_x96,
+                state.liquidity,
+                state.amount_specified_remaining,
+                fee,
+            )?;
+
+            let amount_in_with_fee = to_i128(step.amount_in + step.fee_amount)?;
+            let amount_out = to_i128(step.amount_out)?;
             if exact_input {
-                state.amount_specified_remaining -= (step.amount_in / step.fee_amount) as i128;
-                state.amount_calculated = state.amount_calculated;
+                state.amount_specified_remaining -= amount_in_with_fee;
+                state.amount_calculated -= amount_out;
             } else {
-                state.amount_specified_remaining += step.amount_out as i128;
-                state.amount_calculated = state.amount_calculated;
+                state.amount_specified_remaining += amount_out;
+                state.amount_calculated += amount_in_with_fee;
             }
             // if the protocol fee is on, calculate how much is owed, decrement feeAmount, and increment protocolFee
             if cache.fee_protocol > 0 {
-                let delta = step.fee_amount / cache.fee_protocol as u128;
+                let delta = step.fee_amount / cache.fee_protocol;
                 step.fee_amount -= delta;
-                state.protocol_fee += delta;
+                state.protocol_fee += delta.low_u128();
             }
             // update global fee tracker
-            // TODO: implement full_math
-            // TODO: implement fixed_point_128
             if state.liquidity > 0 {
-                state.fee_growth_global_x128 +=
-                    mul_div(step.fee_amount, u128::MAX, state.liquidity);
+                state.fee_growth_global_x128 = state.fee_growth_global_x128.wrapping_add(mul_div(
+                    step.fee_amount,
+                    Q128,
+                    U256::from(state.liquidity),
+                )?);
             }
             // shift tick if we reached the next price
-            // TODO: implement observe_single
-            // TODO: implement cross
             if state.sqrt_price_x96 == step.sqrt_price_next_x96 {
-                // check for the placeholder value, which we replace with the actual value the first time the swap
-                // crosses an initialized tick
-                if !cache.computed_latest_observations {
-                    (
-                        cache.tick_cumulative,
-                        cache.seconds_per_liquidity_cumulative_x128,
-                    ) = observe_single(
-                        cache.block_timestamp,
-                        0,
-                        slot0_start.tick,
-                        slot0_start.observation_index,
-                        cache.liquidity_start,
-                        slot0_start.observation_cardinality,
-                    );
-                    cache.computed_latest_observations = true;
-                }
-                let mut liquidity_net: i128 = if zero_for_one {
-                    cross(
+                // if the tick is initialized, run the tick transition
+                if step.initialized {
+                    // check for the placeholder value, which we replace with the actual value the first time the swap
+                    // crosses an initialized tick
+                    if !cache.computed_latest_observations {
+                        (
+                            cache.tick_cumulative,
+                            cache.seconds_per_liquidity_cumulative_x128,
+                        ) = observe_single(
+                            &self.data::<data::Data>().observations,
+                            cache.block_timestamp,
+                            0,
+                            slot0_start.tick,
+                            slot0_start.observation_index,
+                            cache.liquidity_start,
+                            slot0_start.observation_cardinality,
+                        )?;
+                        cache.computed_latest_observations = true;
+                    }
+                    let (fee_growth_global_0x128, fee_growth_global_1x128) = if zero_for_one {
+                        (
+                            state.fee_growth_global_x128,
+                            U256(self.data::<data::Data>().fee_growth_global_1x128),
+                        )
+                    } else {
+                        (
+                            U256(self.data::<data::Data>().fee_growth_global_0x128),
+                            state.fee_growth_global_x128,
+                        )
+                    };
+                    let mut liquidity_net: i128 = cross(
+                        &mut self.data::<data::Data>().ticks,
                         step.tick_next,
-                        state.fee_growth_global_x128,
-                        self.data::<data::Data>().fee_growth_global_1x128,
+                        fee_growth_global_0x128,
+                        fee_growth_global_1x128,
                         cache.seconds_per_liquidity_cumulative_x128,
                         cache.tick_cumulative,
                         cache.block_timestamp,
-                    )
+                    );
+                    // if we're moving leftward, we interpret liquidityNet as the opposite sign
+                    // safe because liquidityNet cannot be type(int128).min
+                    if zero_for_one {
+                        liquidity_net = -liquidity_net
+                    };
+                    state.liquidity = add_delta(state.liquidity, liquidity_net)?;
+                }
+                state.tick = if zero_for_one {
+                    step.tick_next - 1
                 } else {
-                    cross(
-                        step.tick_next,
-                        self.data::<data::Data>().fee_growth_global_0x128,
-                        state.fee_growth_global_x128,
-                        cache.seconds_per_liquidity_cumulative_x128,
-                        cache.tick_cumulative,
-                        cache.block_timestamp,
-                    )
-                };
-                // if we're moving leftward, we interpret liquidityNet as the opposite sign
-                // safe because liquidityNet cannot be type(int128).min
-                if zero_for_one {
-                    liquidity_net = -liquidity_net
+                    step.tick_next
                 };
-                //TODO implement add_delta
-                state.liquidity = add_delta(state.liquidity, liquidity_net);
             } else if state.sqrt_price_x96 != step.sqrt_price_start_x96 {
-                state.tick = get_tick_at_sqrt_ratio(state.sqrt_price_x96);
+                // recompute unless we're on a lower tick boundary (i.e. already transitioned ticks), and haven't moved
+                state.tick = get_tick_at_sqrt_ratio(state.sqrt_price_x96)?;
             }
         }
         // update tick and write an oracle entry if the tick change
         if state.tick != slot0_start.tick {
-            let mut observation_index: u16;
-            let mut observation_cardinality: u16;
-            (observation_index, observation_cardinality) = write(
+            let (observation_index, observation_cardinality) = write(
+                &mut self.data::<data::Data>().observations,
                 slot0_start.observation_index,
                 cache.block_timestamp,
                 slot0_start.tick,
@@ -345,14 +417,14 @@ impl<T: Storage<data::Data> + Internal> Pool for T {
                 self.data::<data::Data>().slot_0.observation_index,
                 self.data::<data::Data>().slot_0.observation_cardinality,
             ) = (
-                state.sqrt_price_x96,
+                state.sqrt_price_x96.0,
                 state.tick,
                 observation_index,
                 observation_cardinality,
             );
         } else {
             // otherwise, just update the sqrt price
-            self.data::<data::Data>().slot_0.sqrt_price_x96 = state.sqrt_price_x96;
+            self.data::<data::Data>().slot_0.sqrt_price_x96 = state.sqrt_price_x96.0;
         }
         // update liquidity if it changed
         if cache.liquidity_start != state.liquidity {
@@ -361,40 +433,60 @@ impl<T: Storage<data::Data> + Internal> Pool for T {
         // update fee growth global and, if necessary, protocol fees
         // overflow is acceptable, protocol has to withdraw before it hits type(uint128).max fees
         if zero_for_one {
-            self.data::<data::Data>().fee_growth_global_0x128 = state.fee_growth_global_x128;
+            self.data::<data::Data>().fee_growth_global_0x128 = state.fee_growth_global_x128.0;
             if state.protocol_fee > 0 {
                 // TODO data::Data::fee0 is Balance type
                 self.data::<data::Data>().fee0 += state.protocol_fee;
             }
         } else {
-            self.data::<data::Data>().fee_growth_global_1x128 = state.fee_growth_global_x128;
+            self.data::<data::Data>().fee_growth_global_1x128 = state.fee_growth_global_x128.0;
             if state.protocol_fee > 0 {
                 //TODO data::Data::fee1 is Balance type
                 self.data::<data::Data>().fee1 += state.protocol_fee;
             }
         }
-        let amount_specified_i128 = amount_specified as i128;
-        let amount_specified_remaining_i128 = state.amount_specified_remaining as i128;
-
-        if zero_for_one == exact_input {
-            let amount_1_i128 = -amount_specified_remaining_i128;
-            amount_0 = (amount_specified_i128 - state.amount_specified_remaining) as Balance;
-            amount_1 = state.amount_calculated as Balance;
+        let (amount_0, amount_1) = if zero_for_one == exact_input {
+            (
+                amount_specified - state.amount_specified_remaining,
+                state.amount_calculated,
+            )
         } else {
-            let amount_0_i128 = -amount_specified_remaining_i128;
-            amount_0 = state.amount_calculated as Balance;
-            amount_1 = (amount_specified_i128 - state.amount_specified_remaining) as Balance;
-        }
+            (
+                state.amount_calculated,
+                amount_specified - state.amount_specified_remaining,
+            )
+        };
 
         // do the transfers and collect payment
+        let contract = Self::env().account_id();
         if zero_for_one {
             if amount_1 < 0 {
-                safe_transfer(token_1, recipient, amount_1);
+                safe_transfer(token_1, recipient, amount_1.unsigned_abs())?;
             }
+            let balance_0_before = PSP22Ref::balance_of(&token_0, contract);
+            self.flush();
+            SwapCallbackRef::swap_callback(&caller, amount_0, amount_1, data);
+            let balance_0_expected = balance_0_before
+                .checked_add(amount_0.unsigned_abs())
+                .ok_or(PoolError::AddOverflowBalance0)?;
+            ensure!(
+                balance_0_expected <= PSP22Ref::balance_of(&token_0, contract),
+                PoolError::InsufficientInputAmount
+            );
         } else {
             if amount_0 < 0 {
-                safe_transfer(token_0, recipient, amount_0);
+                safe_transfer(token_0, recipient, amount_0.unsigned_abs())?;
             }
+            let balance_1_before = PSP22Ref::balance_of(&token_1, contract);
+            self.flush();
+            SwapCallbackRef::swap_callback(&caller, amount_0, amount_1, data);
+            let balance_1_expected = balance_1_before
+                .checked_add(amount_1.unsigned_abs())
+                .ok_or(PoolError::AddOverflowBalance1)?;
+            ensure!(
+                balance_1_expected <= PSP22Ref::balance_of(&token_1, contract),
+                PoolError::InsufficientInputAmount
+            );
         }
 
         self._emit_swap_event(
@@ -406,10 +498,10 @@ impl<T: Storage<data::Data> + Internal> Pool for T {
             state.liquidity,
             state.tick,
         );
-        self.data::<data::Data>().slot_0.unlocked = true;
         Ok((amount_0, amount_1))
     }
 
+    #[modifiers(lock)]
     fn flash(
         &mut self,
         recipient: AccountId,
@@ -417,59 +509,280 @@ impl<T: Storage<data::Data> + Internal> Pool for T {
         amount1: u128,
         data: Vec<u8>,
     ) -> Result<(), PoolError> {
+        let liquidity = self.data::<data::Data>().liquidity;
+        ensure!(liquidity > 0, PoolError::ZeroLiquidity);
+
+        let fee = U256::from(self.data::<data::Data>().fee);
+        // the fees never exceed the borrowed amounts, so they fit back into a Balance
+        let fee_0 =
+            mul_div_rounding_up(U256::from(amount0), fee, U256::from(1_000_000))?.low_u128();
+        let fee_1 =
+            mul_div_rounding_up(U256::from(amount1), fee, U256::from(1_000_000))?.low_u128();
+
+        let contract = Self::env().account_id();
+        let caller = Self::env().caller();
+        let token_0 = self.data::<data::Data>().token_0;
+        let token_1 = self.data::<data::Data>().token_1;
+        let balance_0_before = PSP22Ref::balance_of(&token_0, contract);
+        let balance_1_before = PSP22Ref::balance_of(&token_1, contract);
+
+        if amount0 > 0 {
+            safe_transfer(token_0, recipient, amount0)?;
+        }
+        if amount1 > 0 {
+            safe_transfer(token_1, recipient, amount1)?;
+        }
+
+        self.flush();
+        FlashCallbackRef::flash_callback(&caller, fee_0, fee_1, data);
+
+        let balance_0_after = PSP22Ref::balance_of(&token_0, contract);
+        let balance_1_after = PSP22Ref::balance_of(&token_1, contract);
+        let balance_0_expected = balance_0_before
+            .checked_add(fee_0)
+            .ok_or(PoolError::AddOverflowBalance0)?;
+        ensure!(balance_0_expected <= balance_0_after, PoolError::F0);
+        let balance_1_expected = balance_1_before
+            .checked_add(fee_1)
+            .ok_or(PoolError::AddOverflowBalance1)?;
+        ensure!(balance_1_expected <= balance_1_after, PoolError::F1);
+
+        // sub is safe because we know balance_after is gt balance_before by at least fee
+        let paid_0 = balance_0_after - balance_0_before;
+        let paid_1 = balance_1_after - balance_1_before;
+
+        let fee_protocol = self.data::<data::Data>().slot_0.fee_protocol;
+        if paid_0 > 0 {
+            let fee_protocol_0 = fee_protocol % 16;
+            let fees_0 = if fee_protocol_0 == 0 {
+                0
+            } else {
+                paid_0 / fee_protocol_0 as u128
+            };
+            if fees_0 > 0 {
+                self.data::<data::Data>().fee0 += fees_0;
+            }
+            let fee_growth = mul_div(U256::from(paid_0 - fees_0), Q128, U256::from(liquidity))?;
+            self.data::<data::Data>().fee_growth_global_0x128 =
+                U256(self.data::<data::Data>().fee_growth_global_0x128)
+                    .wrapping_add(fee_growth)
+                    .0;
+        }
+        if paid_1 > 0 {
+            let fee_protocol_1 = fee_protocol >> 4;
+            let fees_1 = if fee_protocol_1 == 0 {
+                0
+            } else {
+                paid_1 / fee_protocol_1 as u128
+            };
+            if fees_1 > 0 {
+                self.data::<data::Data>().fee1 += fees_1;
+            }
+            let fee_growth = mul_div(U256::from(paid_1 - fees_1), Q128, U256::from(liquidity))?;
+            self.data::<data::Data>().fee_growth_global_1x128 =
+                U256(self.data::<data::Data>().fee_growth_global_1x128)
+                    .wrapping_add(fee_growth)
+                    .0;
+        }
+
+        self._emit_flash_event(caller, recipient, amount0, amount1, paid_0, paid_1);
         Ok(())
     }
 
-    // ProtocolOwnerActions
-    // TODO: add fee protocol
+    #[modifiers(lock, only_factory_owner)]
     fn collect_protocol(
         &mut self,
-        sender: AccountId,
         recipient: AccountId,
         amount0_requested: Balance,
         amount1_requested: Balance,
     ) -> Result<(Balance, Balance), PoolError> {
-        let caller = Self::env().caller();
-        let mut amount_0: Balance;
-        let mut amount_1: Balance;
-        let mut fee_0 = self.data::<data::Data>().fee0;
-        let mut fee_1 = self.data::<data::Data>().fee1;
-        let token_0 = self.data::<data::Data>().token_0;
-        let token_1 = self.data::<data::Data>().token_1;
-        if amount0_requested > fee_0 {
-            amount_0 = fee_0;
-        } else {
-            amount_0 = amount0_requested;
-        }
-        if amount1_requested > fee_1 {
-            amount_1 = fee_1;
-        } else {
-            amount_1 = amount1_requested;
-        }
+        let fee_0 = self.data::<data::Data>().fee0;
+        let fee_1 = self.data::<data::Data>().fee1;
+        let mut amount_0 = amount0_requested.min(fee_0);
+        let mut amount_1 = amount1_requested.min(fee_1);
+
         if amount_0 > 0 {
+            // ensure that the slot is not cleared
             if amount_0 == fee_0 {
                 amount_0 -= 1;
             }
-            fee_0 -= amount_0;
-            safe_transfer(token_0, recipient, amount_0);
+            self.data::<data::Data>().fee0 = fee_0 - amount_0;
         }
         if amount_1 > 0 {
             if amount_1 == fee_1 {
                 amount_1 -= 1;
             }
-            fee_1 -= amount_1;
-            safe_transfer(token_1, recipient, amount_1);
+            self.data::<data::Data>().fee1 = fee_1 - amount_1;
+        }
+        if amount_0 > 0 {
+            safe_transfer(self.data::<data::Data>().token_0, recipient, amount_0)?;
+        }
+        if amount_1 > 0 {
+            safe_transfer(self.data::<data::Data>().token_1, recipient, amount_1)?;
         }
-        self._emit_collect_protocol_event(caller, recipient, amount_0, amount_1);
+
+        self._emit_collect_protocol_event(Self::env().caller(), recipient, amount_0, amount_1);
         Ok((amount_0, amount_1))
     }
 
+    #[modifiers(lock, only_factory_owner)]
+    fn set_fee_protocol(&mut self, fee_protocol0: u8, fee_protocol1: u8) -> Result<(), PoolError> {
+        ensure!(
+            (fee_protocol0 == 0 || (4..=10).contains(&fee_protocol0))
+                && (fee_protocol1 == 0 || (4..=10).contains(&fee_protocol1)),
+            PoolError::InvalidFeeProtocol
+        );
+        let fee_protocol_old = self.data::<data::Data>().slot_0.fee_protocol;
+        self.data::<data::Data>().slot_0.fee_protocol = fee_protocol0 + (fee_protocol1 << 4);
+        self._emit_set_fee_protocol_event(
+            fee_protocol_old % 16,
+            fee_protocol_old >> 4,
+            fee_protocol0,
+            fee_protocol1,
+        );
+        Ok(())
+    }
+
+    fn observe(&self, seconds_agos: Vec<u32>) -> Result<(Vec<i64>, Vec<U256>), PoolError> {
+        self._require_unlocked()?;
+        let slot_0 = self.data::<data::Data>().slot_0;
+        Ok(oracle::observe(
+            &self.data::<data::Data>().observations,
+            self._block_timestamp(),
+            &seconds_agos,
+            slot_0.tick,
+            slot_0.observation_index,
+            self.data::<data::Data>().liquidity,
+            slot_0.observation_cardinality,
+        )?)
+    }
+
+    fn snapshot_cumulatives_inside(
+        &self,
+        tick_lower: i32,
+        tick_upper: i32,
+    ) -> Result<(i64, U256, u32), PoolError> {
+        self._require_unlocked()?;
+        ensure!(
+            self._check_ticks(tick_lower, tick_upper),
+            PoolError::TickError
+        );
+        let lower = self
+            .data::<data::Data>()
+            .ticks
+            .get(&tick_lower)
+            .unwrap_or_default();
+        ensure!(lower.initialized, PoolError::TickNotInitialized);
+        let upper = self
+            .data::<data::Data>()
+            .ticks
+            .get(&tick_upper)
+            .unwrap_or_default();
+        ensure!(upper.initialized, PoolError::TickNotInitialized);
+        let lower_seconds_per_liquidity_outside_x128 =
+            U256(lower.seconds_per_liquidity_outside_x128);
+        let upper_seconds_per_liquidity_outside_x128 =
+            U256(upper.seconds_per_liquidity_outside_x128);
+
+        let slot_0 = self.data::<data::Data>().slot_0;
+        if slot_0.tick < tick_lower {
+            Ok((
+                lower
+                    .tick_cumulative_outside
+                    .wrapping_sub(upper.tick_cumulative_outside),
+                lower_seconds_per_liquidity_outside_x128
+                    .wrapping_sub(upper_seconds_per_liquidity_outside_x128),
+                lower.seconds_outside.wrapping_sub(upper.seconds_outside),
+            ))
+        } else if slot_0.tick < tick_upper {
+            let time = self._block_timestamp();
+            let (tick_cumulative, seconds_per_liquidity_cumulative_x128) = observe_single(
+                &self.data::<data::Data>().observations,
+                time,
+                0,
+                slot_0.tick,
+                slot_0.observation_index,
+                self.data::<data::Data>().liquidity,
+                slot_0.observation_cardinality,
+            )?;
+            Ok((
+                tick_cumulative
+                    .wrapping_sub(lower.tick_cumulative_outside)
+                    .wrapping_sub(upper.tick_cumulative_outside),
+                seconds_per_liquidity_cumulative_x128
+                    .wrapping_sub(lower_seconds_per_liquidity_outside_x128)
+                    .wrapping_sub(upper_seconds_per_liquidity_outside_x128),
+                time.wrapping_sub(lower.seconds_outside)
+                    .wrapping_sub(upper.seconds_outside),
+            ))
+        } else {
+            Ok((
+                upper
+                    .tick_cumulative_outside
+                    .wrapping_sub(lower.tick_cumulative_outside),
+                upper_seconds_per_liquidity_outside_x128
+                    .wrapping_sub(lower_seconds_per_liquidity_outside_x128),
+                upper.seconds_outside.wrapping_sub(lower.seconds_outside),
+            ))
+        }
+    }
+
+    #[modifiers(lock)]
+    fn increase_observation_cardinality_next(
+        &mut self,
+        observation_cardinality_next: u16,
+    ) -> Result<(), PoolError> {
+        // for the event
+        let observation_cardinality_next_old = self
+            .data::<data::Data>()
+            .slot_0
+            .observation_cardinality_next;
+        let observation_cardinality_next_new = grow(
+            &mut self.data::<data::Data>().observations,
+            observation_cardinality_next_old,
+            observation_cardinality_next,
+        )?;
+        self.data::<data::Data>()
+            .slot_0
+            .observation_cardinality_next = observation_cardinality_next_new;
+        if observation_cardinality_next_old != observation_cardinality_next_new {
+            self._emit_increase_observation_cardinality_next_event(
+                observation_cardinality_next_old,
+                observation_cardinality_next_new,
+            );
+        }
+        Ok(())
+    }
+
     fn protocol_fees(&self) -> Result<(Balance, Balance), PoolError> {
+        self._require_unlocked()?;
         let fee_0 = self.data::<data::Data>().fee0;
         let fee_1 = self.data::<data::Data>().fee1;
         Ok((fee_0, fee_1))
     }
 
+    #[modifiers(no_callback, only_factory_owner)]
+    fn set_code_hash(&mut self, code_hash: [u8; 32]) -> Result<(), PoolError> {
+        ink_env::set_code_hash(&code_hash).map_err(|_| PoolError::SetCodeHashFailed)
+    }
+
+    fn storage_version(&self) -> u32 {
+        self.data::<data::Data>().version
+    }
+
+    #[modifiers(no_callback, only_factory_owner)]
+    fn migrate(&mut self) -> Result<(), PoolError> {
+        let version = self.data::<data::Data>().version;
+        ensure!(version < data::STORAGE_VERSION, PoolError::StorageUpToDate);
+        self._migrate(version)?;
+        self.data::<data::Data>().version = data::STORAGE_VERSION;
+        Ok(())
+    }
+
+    default fn _migrate(&mut self, _from_version: u32) -> Result<(), PoolError> {
+        Ok(())
+    }
+
     fn get_token_0(&self) -> AccountId {
         self.data::<data::Data>().token_0
     }
@@ -494,24 +807,33 @@ impl<T: Storage<data::Data> + Internal> Pool for T {
     //     self.data::<data::Data>().slot0
     // }
 
-    fn get_fee_growth_global_0x128(&self) -> u128 {
-        self.data::<data::Data>().fee_growth_global_0x128
+    fn get_fee_growth_global_0x128(&self) -> Result<U256, PoolError> {
+        self._require_unlocked()?;
+        Ok(U256(self.data::<data::Data>().fee_growth_global_0x128))
     }
 
-    fn get_fee_growth_global_1x128(&self) -> u128 {
-        self.data::<data::Data>().fee_growth_global_1x128
+    fn get_fee_growth_global_1x128(&self) -> Result<U256, PoolError> {
+        self._require_unlocked()?;
+        Ok(U256(self.data::<data::Data>().fee_growth_global_1x128))
     }
 
     // fn get_protocol_fees(&self) -> ProtocolFees{
     //     self.data::<data::Data>().protocol_fees
     // }
 
-    fn get_liquidity(&self) -> u128 {
-        self.data::<data::Data>().liquidity
+    fn get_liquidity(&self) -> Result<u128, PoolError> {
+        self._require_unlocked()?;
+        Ok(self.data::<data::Data>().liquidity)
+    }
+
+    fn get_tick(&self, tick: i32) -> Result<Option<TickInfo>, PoolError> {
+        self._require_unlocked()?;
+        Ok(self.data::<data::Data>().ticks.get(&tick))
     }
 
-    fn get_tick_bitmap(&self, entry: i32) -> Option<u128> {
-        self.data::<data::Data>().tick_bitmap.get(&entry)
+    fn get_tick_bitmap(&self, word_pos: i16) -> Result<U256, PoolError> {
+        self._require_unlocked()?;
+        Ok(word(&self.data::<data::Data>().tick_bitmap, word_pos))
     }
 
     // fn get_tick_spacing(&self) -> i32 {
@@ -522,8 +844,9 @@ impl<T: Storage<data::Data> + Internal> Pool for T {
     //     self.data::<data::Data>().max_liquidity_per_tick
     // }
 
-    fn get_slot_0(&self) -> Slot {
-        self.data::<data::Data>().slot_0
+    fn get_slot_0(&self) -> Result<Slot, PoolError> {
+        self._require_unlocked()?;
+        Ok(self.data::<data::Data>().slot_0)
     }
 
     // fn get_fee_growth_global_0x128(&self) -> u128 {
@@ -538,58 +861,62 @@ impl<T: Storage<data::Data> + Internal> Pool for T {
     //     self.data::<data::Data>().liquidity
     // }
 
+    #[modifiers(lock)]
     fn mint(
         &mut self,
         recipient: AccountId,
         tick_lower: i32,
         tick_upper: i32,
         amount: u128,
-        data: u128,
-    ) -> Result<(u128, u128), PoolError> {
-        // need to convert to u256
-        if amount == 0 {
-            return Err(PoolError::ZeroAmmount);
-        }
-        //need to implement a better solution for conversion
-        let amount_i128 = -(amount as i128);
+        data: Vec<u8>,
+    ) -> Result<(Balance, Balance), PoolError> {
+        ensure!(amount > 0, PoolError::ZeroAmmount);
+        let liquidity_delta = i128::try_from(amount).map_err(|_| PoolError::CastOverflow)?;
 
         let (_, amount_0_int, amount_1_int) =
-            self._modify_position(recipient, tick_lower, tick_upper, amount_i128)?;
+            self._modify_position(recipient, tick_lower, tick_upper, liquidity_delta)?;
 
-        let amount_0 = amount_0_int as u128;
-        let amount_1 = amount_1_int as u128;
+        // adding liquidity only ever yields non-negative deltas
+        let amount_0 = amount_0_int.unsigned_abs();
+        let amount_1 = amount_1_int.unsigned_abs();
 
         let contract = Self::env().account_id();
+        let caller = Self::env().caller();
+        let token_0 = self.data::<data::Data>().token_0;
+        let token_1 = self.data::<data::Data>().token_1;
 
-        let balance_0_before: u128;
-        let balance_1_before: u128;
-
-        // need to implement: IUniswapV3MintCallback(msg.sender).uniswapV3MintCallback(amount0, amount1, data)
+        let balance_0_before = if amount_0 > 0 {
+            PSP22Ref::balance_of(&token_0, contract)
+        } else {
+            0
+        };
+        let balance_1_before = if amount_1 > 0 {
+            PSP22Ref::balance_of(&token_1, contract)
+        } else {
+            0
+        };
+        self.flush();
+        MintCallbackRef::mint_callback(&caller, amount_0, amount_1, data);
         if amount_0 > 0 {
-            balance_0_before = PSP22Ref::balance_of(&self.data::<data::Data>().token_0, contract);
-            let balance_0_before_plus_amount_0 = balance_0_before
+            let balance_0_expected = balance_0_before
                 .checked_add(amount_0)
                 .ok_or(PoolError::AddOverflowBalance0)?;
-            if balance_0_before_plus_amount_0 > balance_0_before {
-                return Err(PoolError::M0);
-            }
+            ensure!(
+                balance_0_expected <= PSP22Ref::balance_of(&token_0, contract),
+                PoolError::M0
+            );
         }
         if amount_1 > 0 {
-            balance_1_before = PSP22Ref::balance_of(&self.data::<data::Data>().token_1, contract);
-            let balance_1_before_plus_amount_1 = balance_1_before
+            let balance_1_expected = balance_1_before
                 .checked_add(amount_1)
                 .ok_or(PoolError::AddOverflowBalance1)?;
-            if balance_1_before_plus_amount_1 > balance_1_before {
-                return Err(PoolError::M1);
-            }
+            ensure!(
+                balance_1_expected <= PSP22Ref::balance_of(&token_1, contract),
+                PoolError::M1
+            );
         }
         self._emit_mint_event(
-            Self::env().caller(),
-            tick_lower,
-            tick_upper,
-            amount,
-            amount_0,
-            amount_1,
+            recipient, tick_lower, tick_upper, amount, amount_0, amount_1,
         );
         Ok((amount_0, amount_1))
     }
@@ -605,26 +932,32 @@ impl<T: Storage<data::Data> + Internal> Pool for T {
     ) {
     }
 
-    // fn _update_position(&self, onwer: AccountId, tick_lower: i32, tick_upper: i32, liquidity_delta: i128, tick: i32) -> Result<Balance, PoolError>{
-    //     let position = self.get_position(owner,tick_lower, tick_upper)?;
-    //     let flipped_lower = false;
-    //     let flipped_upper = false;
-
-    //     if liquidity_delta != 0 {
-    //         let time = Self::env().block_timestamp() as u32;
-    //     }
-
-    // }
-
     fn get_position(
         &self,
         owner: AccountId,
         tick_lower: i32,
         tick_upper: i32,
-    ) -> Option<PositionInfo> {
-        self.data::<data::Data>()
+    ) -> Result<Option<PositionInfo>, PoolError> {
+        self._require_unlocked()?;
+        Ok(self
+            .data::<data::Data>()
             .positions
-            .get(&Self::env().hash_encoded::<Blake2x256, _>(&(owner, tick_lower, tick_upper)))
+            .get(&position::key(owner, tick_lower, tick_upper)))
+    }
+
+    fn _require_unlocked(&self) -> Result<(), PoolError> {
+        let slot_0 = self.data::<data::Data>().slot_0;
+        ensure!(
+            !U256(slot_0.sqrt_price_x96).is_zero(),
+            PoolError::NotInitialized
+        );
+        ensure!(slot_0.unlocked, PoolError::PoolIsLocked);
+        Ok(())
+    }
+
+    fn _block_timestamp(&self) -> u32 {
+        // ink! timestamps are in milliseconds, the oracle works with truncated 32-bit seconds like Uniswap
+        (Self::env().block_timestamp() / 1000) as u32
     }
 
     fn _modify_position(
@@ -632,83 +965,208 @@ impl<T: Storage<data::Data> + Internal> Pool for T {
         owner: AccountId,
         tick_lower: i32,
         tick_upper: i32,
-        liqudiity_delta: i128,
-    ) -> Result<(PositionInfo, Balance, Balance), PoolError> {
-        if !self._check_ticks(tick_lower, tick_upper) {
-            return Err(PoolError::TickError);
-        }
-        let slot0 = self.get_slot_0();
-        // need to implement _updatePosition
+        liquidity_delta: i128,
+    ) -> Result<(PositionInfo, i128, i128), PoolError> {
+        ensure!(
+            self._check_ticks(tick_lower, tick_upper),
+            PoolError::TickError
+        );
+        let slot_0 = self.data::<data::Data>().slot_0;
 
-        let amount_0: u128 = 0;
-        let amount_1: u128 = 0;
+        let position =
+            self._update_position(owner, tick_lower, tick_upper, liquidity_delta, slot_0.tick)?;
 
-        // if liquidity_delta != 0{
-        //     if slot0.tick < tick_lower{
+        let mut amount_0 = 0;
+        let mut amount_1 = 0;
+        if liquidity_delta != 0 {
+            let sqrt_ratio_lower_x96 = get_sqrt_ratio_at_tick(tick_lower)?;
+            let sqrt_ratio_upper_x96 = get_sqrt_ratio_at_tick(tick_upper)?;
+            if slot_0.tick < tick_lower {
+                // current tick is below the passed range; liquidity can only become in range by crossing from left to
+                // right, when we'll need _more_ token_0 (it's becoming more valuable) so user must provide it
+                amount_0 = get_amount0_delta_signed(
+                    sqrt_ratio_lower_x96,
+                    sqrt_ratio_upper_x96,
+                    liquidity_delta,
+                )?;
+            } else if slot_0.tick < tick_upper {
+                // current tick is inside the passed range
+                let liquidity_before = self.data::<data::Data>().liquidity;
 
-        //     }
-        // }
+                // write an oracle entry
+                let (observation_index, observation_cardinality) = write(
+                    &mut self.data::<data::Data>().observations,
+                    slot_0.observation_index,
+                    self._block_timestamp(),
+                    slot_0.tick,
+                    liquidity_before,
+                    slot_0.observation_cardinality,
+                    slot_0.observation_cardinality_next,
+                );
+                self.data::<data::Data>().slot_0.observation_index = observation_index;
+                self.data::<data::Data>().slot_0.observation_cardinality = observation_cardinality;
 
-        let position = PositionInfo {
-            liquidity: 0,
-            fee_growth_inside_0_last_x128: 0,
-            fee_growth_inside_1_last_x128: 0,
-            tokens_owed_0: 0,
-            tokens_owed_1: 0,
-        };
+                let sqrt_price_x96 = U256(slot_0.sqrt_price_x96);
+                amount_0 = get_amount0_delta_signed(
+                    sqrt_price_x96,
+                    sqrt_ratio_upper_x96,
+                    liquidity_delta,
+                )?;
+                amount_1 = get_amount1_delta_signed(
+                    sqrt_ratio_lower_x96,
+                    sqrt_price_x96,
+                    liquidity_delta,
+                )?;
+
+                self.data::<data::Data>().liquidity = add_delta(liquidity_before, liquidity_delta)?;
+            } else {
+                // current tick is above the passed range; liquidity can only become in range by crossing from right to
+                // left, when we'll need _more_ token_1 (it's becoming more valuable) so user must provide it
+                amount_1 = get_amount1_delta_signed(
+                    sqrt_ratio_lower_x96,
+                    sqrt_ratio_upper_x96,
+                    liquidity_delta,
+                )?;
+            }
+        }
         Ok((position, amount_0, amount_1))
     }
 
+    fn _update_position(
+        &mut self,
+        owner: AccountId,
+        tick_lower: i32,
+        tick_upper: i32,
+        liquidity_delta: i128,
+        tick: i32,
+    ) -> Result<PositionInfo, PoolError> {
+        let fee_growth_global_0x128 = U256(self.data::<data::Data>().fee_growth_global_0x128);
+        let fee_growth_global_1x128 = U256(self.data::<data::Data>().fee_growth_global_1x128);
+
+        // if we need to update the ticks, do it
+        let mut flipped_lower = false;
+        let mut flipped_upper = false;
+        if liquidity_delta != 0 {
+            let time = self._block_timestamp();
+            let slot_0 = self.data::<data::Data>().slot_0;
+            let (tick_cumulative, seconds_per_liquidity_cumulative_x128) = observe_single(
+                &self.data::<data::Data>().observations,
+                time,
+                0,
+                slot_0.tick,
+                slot_0.observation_index,
+                self.data::<data::Data>().liquidity,
+                slot_0.observation_cardinality,
+            )?;
+
+            let max_liquidity_per_tick = self.data::<data::Data>().max_liquidity_per_tick;
+            flipped_lower = tick::update(
+                &mut self.data::<data::Data>().ticks,
+                tick_lower,
+                tick,
+                liquidity_delta,
+                fee_growth_global_0x128,
+                fee_growth_global_1x128,
+                seconds_per_liquidity_cumulative_x128,
+                tick_cumulative,
+                time,
+                false,
+                max_liquidity_per_tick,
+            )?;
+            flipped_upper = tick::update(
+                &mut self.data::<data::Data>().ticks,
+                tick_upper,
+                tick,
+                liquidity_delta,
+                fee_growth_global_0x128,
+                fee_growth_global_1x128,
+                seconds_per_liquidity_cumulative_x128,
+                tick_cumulative,
+                time,
+                true,
+                max_liquidity_per_tick,
+            )?;
+
+            let tick_spacing = self.data::<data::Data>().tick_spacing;
+            if flipped_lower {
+                flip_tick(
+                    &mut self.data::<data::Data>().tick_bitmap,
+                    tick_lower,
+                    tick_spacing,
+                )?;
+            }
+            if flipped_upper {
+                flip_tick(
+                    &mut self.data::<data::Data>().tick_bitmap,
+                    tick_upper,
+                    tick_spacing,
+                )?;
+            }
+        }
+
+        let (fee_growth_inside_0x128, fee_growth_inside_1x128) = get_fee_growth_inside(
+            &self.data::<data::Data>().ticks,
+            tick_lower,
+            tick_upper,
+            tick,
+            fee_growth_global_0x128,
+            fee_growth_global_1x128,
+        );
+
+        let position = position::update(
+            &mut self.data::<data::Data>().positions,
+            owner,
+            tick_lower,
+            tick_upper,
+            liquidity_delta,
+            fee_growth_inside_0x128,
+            fee_growth_inside_1x128,
+        )?;
+
+        // clear any tick data that is no longer needed
+        if liquidity_delta < 0 {
+            if flipped_lower {
+                tick::clear(&mut self.data::<data::Data>().ticks, tick_lower);
+            }
+            if flipped_upper {
+                tick::clear(&mut self.data::<data::Data>().ticks, tick_upper);
+            }
+        }
+        Ok(position)
+    }
+
     fn _check_ticks(&self, tick_lower: i32, tick_upper: i32) -> bool {
-        tick_lower < tick_upper && tick_lower >= i32::MIN && tick_upper <= i32::MAX
+        tick_lower < tick_upper && tick_lower >= MIN_TICK && tick_upper <= MAX_TICK
     }
 
+    #[modifiers(lock)]
     fn burn(
         &mut self,
         tick_lower: i32,
         tick_upper: i32,
         amount: u128,
     ) -> Result<(Balance, Balance), PoolError> {
-        let amount_i128 = amount as i128;
+        let liquidity_delta = i128::try_from(amount)
+            .map_err(|_| PoolError::CastOverflow)?
+            .wrapping_neg();
+        let caller = Self::env().caller();
 
-        let (position, amount_0_int, amount_1_int) =
-            self._modify_position(Self::env().caller(), tick_lower, tick_upper, amount_i128)?;
+        let (mut position, amount_0_int, amount_1_int) =
+            self._modify_position(caller, tick_lower, tick_upper, liquidity_delta)?;
 
-        let amount_0: u128 = amount_0_int.checked_neg().ok_or(PoolError::CheckedNeg0)?;
-        let amount_1: u128 = amount_1_int.checked_neg().ok_or(PoolError::CheckedNeg1)?;
+        // removing liquidity only ever yields non-positive deltas
+        let amount_0 = amount_0_int.unsigned_abs();
+        let amount_1 = amount_1_int.unsigned_abs();
 
         if amount_0 > 0 || amount_1 > 0 {
+            position.tokens_owed_0 = position.tokens_owed_0.wrapping_add(amount_0);
+            position.tokens_owed_1 = position.tokens_owed_1.wrapping_add(amount_1);
             self.data::<data::Data>()
                 .positions
-                .get(&Self::env().hash_encoded::<Blake2x256, _>(&(
-                    Self::env().caller(),
-                    tick_lower,
-                    tick_upper,
-                )))
-                .unwrap()
-                .tokens_owed_0 = position.tokens_owed_0 + amount_0;
-
-            self.data::<data::Data>()
-                .positions
-                .get(&Self::env().hash_encoded::<Blake2x256, _>(&(
-                    Self::env().caller(),
-                    tick_lower,
-                    tick_upper,
-                )))
-                .unwrap()
-                .tokens_owed_1 = position.tokens_owed_1 + amount_1;
-        } else {
-            return Err(PoolError::BurningInsuficientBalance);
+                .insert(&position::key(caller, tick_lower, tick_upper), &position);
         }
 
-        self._emit_burn_event(
-            Self::env().caller(),
-            tick_lower,
-            tick_upper,
-            amount,
-            amount_0,
-            amount_1,
-        );
+        self._emit_burn_event(caller, tick_lower, tick_upper, amount, amount_0, amount_1);
         Ok((amount_0, amount_1))
     }
 
@@ -723,3 +1181,9 @@ impl<T: Storage<data::Data> + Internal> Pool for T {
     ) {
     }
 }
+
+#[inline]
+fn to_i128(value: U256) -> Result<i128, PoolError> {
+    ensure!(value <= U256::from(i128::MAX), PoolError::CastOverflow);
+    Ok(value.low_u128() as i128)
+}
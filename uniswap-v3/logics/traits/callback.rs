@@ -0,0 +1,45 @@
+use ink_prelude::vec::Vec;
+use openbrush::traits::Balance;
+
+#[openbrush::wrapper]
+pub type MintCallbackRef = dyn MintCallback;
+
+#[openbrush::wrapper]
+pub type SwapCallbackRef = dyn SwapCallback;
+
+#[openbrush::wrapper]
+pub type FlashCallbackRef = dyn FlashCallback;
+
+/// Any contract that calls `Pool::mint` must implement this trait
+#[openbrush::trait_definition]
+pub trait MintCallback {
+    /// Called on the caller after minting liquidity to a position from `Pool::mint`
+    ///
+    /// The implementation must pay the pool the tokens owed for the minted liquidity.
+    /// The caller of this method must be checked to be a pool deployed by the factory.
+    #[ink(message)]
+    fn mint_callback(&mut self, amount_0_owed: Balance, amount_1_owed: Balance, data: Vec<u8>);
+}
+
+/// Any contract that calls `Pool::swap` must implement this trait
+#[openbrush::trait_definition]
+pub trait SwapCallback {
+    /// Called on the caller after executing a swap via `Pool::swap`
+    ///
+    /// `amount_0_delta` and `amount_1_delta` are the amounts of token_0 and token_1 that were sent (negative) or
+    /// must be received (positive) by the pool by the end of the swap. The implementation must pay the pool the
+    /// positive delta. The caller of this method must be checked to be a pool deployed by the factory.
+    #[ink(message)]
+    fn swap_callback(&mut self, amount_0_delta: i128, amount_1_delta: i128, data: Vec<u8>);
+}
+
+/// Any contract that calls `Pool::flash` must implement this trait
+#[openbrush::trait_definition]
+pub trait FlashCallback {
+    /// Called on the caller after transferring to the recipient from `Pool::flash`
+    ///
+    /// The implementation must repay the pool the tokens sent by flash plus the computed fee amounts.
+    /// The caller of this method must be checked to be a pool deployed by the factory.
+    #[ink(message)]
+    fn flash_callback(&mut self, fee_0: Balance, fee_1: Balance, data: Vec<u8>);
+}
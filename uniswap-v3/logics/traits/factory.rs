@@ -1,4 +1,6 @@
-use openbrush::{contracts::traits::ownable::*, traits::AccountId};
+use ink_env::Hash;
+use ink_prelude::vec::Vec;
+use openbrush::traits::AccountId;
 
 #[openbrush::wrapper]
 pub type FactoryRef = dyn Factory;
@@ -13,20 +15,114 @@ pub trait Factory {
         fee: u32,
     ) -> Result<AccountId, FactoryError>;
 
-    fn _instantiate_pool(&mut self, salt_bytes: &[u8]) -> Result<AccountId, FactoryError>;
+    fn _instantiate_pool(
+        &mut self,
+        salt_bytes: &[u8],
+        token_0: AccountId,
+        token_1: AccountId,
+        fee: u32,
+        tick_spacing: i32,
+    ) -> Result<AccountId, FactoryError>;
 
+    /// Returns the current owner of the factory
     #[ink(message)]
-    fn set_owner(&mut self, new_owner: AccountId) -> Result<(), FactoryError>;
+    fn owner(&self) -> AccountId;
 
+    /// Returns the account the ownership was proposed to, if it has not been accepted yet
+    #[ink(message)]
+    fn pending_owner(&self) -> Option<AccountId>;
+
+    /// Proposes `new_owner` as the owner of the factory, replacing any pending proposal
+    ///
+    /// The ownership is only transferred once `new_owner` calls `accept_owner`.
+    #[ink(message)]
+    fn propose_owner(&mut self, new_owner: AccountId) -> Result<(), FactoryError>;
+
+    /// Accepts the ownership proposed to the caller
+    #[ink(message)]
+    fn accept_owner(&mut self) -> Result<(), FactoryError>;
+
+    /// Enables a fee amount with the given tick_spacing, fee amounts may never be removed once enabled
     #[ink(message)]
     fn enable_fee_amount(&mut self, fee: u32, tick_spacing: i32) -> Result<(), FactoryError>;
 
+    fn _enable_fee_amount(&mut self, fee: u32, tick_spacing: i32);
+
     #[ink(message)]
     fn get_fee_amount_tick_spacing(&self, fee: u32) -> Option<i32>;
 
     #[ink(message)]
     fn get_pool(&self, token_a: AccountId, token_b: AccountId, fee: u32) -> Option<AccountId>;
 
+    /// Returns the number of pools created by the factory
+    #[ink(message)]
+    fn all_pools_length(&self) -> u64;
+
+    /// Returns the pool created at `index`, in creation order
+    #[ink(message)]
+    fn all_pools(&self, index: u64) -> Option<AccountId>;
+
+    /// Returns the number of pools that have `token` as token_0 or token_1
+    #[ink(message)]
+    fn pools_for_token_length(&self, token: AccountId) -> u64;
+
+    /// Returns up to `limit` pools that have `token` as token_0 or token_1, starting at index `start`
+    /// in creation order
+    #[ink(message)]
+    fn pools_for_token(&self, token: AccountId, start: u64, limit: u64) -> Vec<AccountId>;
+
+    /// Returns the number of pools of the pair, in either token order
+    #[ink(message)]
+    fn pools_for_pair_length(&self, token_a: AccountId, token_b: AccountId) -> u64;
+
+    /// Returns up to `limit` pools of the pair, in either token order, starting at index `start`
+    /// in creation order
+    #[ink(message)]
+    fn pools_for_pair(
+        &self,
+        token_a: AccountId,
+        token_b: AccountId,
+        start: u64,
+        limit: u64,
+    ) -> Vec<AccountId>;
+
+    /// Returns true if `account` is a pool created by the factory
+    #[ink(message)]
+    fn is_pool(&self, account: AccountId) -> bool;
+
+    /// Returns the code hash new pools are instantiated with
+    #[ink(message)]
+    fn pool_code_hash(&self) -> Hash;
+
+    /// Sets the code hash new pools are instantiated with, can only be called by the owner
+    ///
+    /// Existing pools keep their code, and `compute_pool_address` must be given the code hash a pool
+    /// was created with.
+    #[ink(message)]
+    fn set_pool_code_hash(&mut self, code_hash: [u8; 32]) -> Result<(), FactoryError>;
+
+    /// Replaces the code of the factory, can only be called by the owner, see `migrate`
+    #[ink(message)]
+    fn set_code_hash(&mut self, code_hash: [u8; 32]) -> Result<(), FactoryError>;
+
+    #[ink(message)]
+    fn storage_version(&self) -> u32;
+
+    /// Upgrades the storage written by a previous code to the current `STORAGE_VERSION`, can only be
+    /// called by the owner
+    ///
+    /// The factory and the pools are upgraded the same way: `set_code_hash` keeps the storage as is, so a
+    /// code changing the storage layout bumps `STORAGE_VERSION` and converts the previous layouts in
+    /// `_migrate`, and the owner calls `migrate` right after `set_code_hash`. Fails with `StorageUpToDate`
+    /// once the storage is at the current version.
+    #[ink(message)]
+    fn migrate(&mut self) -> Result<(), FactoryError>;
+
+    /// Migration hook, upgrades the storage from `from_version` to `STORAGE_VERSION`
+    fn _migrate(&mut self, from_version: u32) -> Result<(), FactoryError>;
+
+    fn _register_pool(&mut self, token_0: AccountId, token_1: AccountId, pool: AccountId);
+
     // Events
     fn _emit_owner_changed_event(&self, _original_owner: AccountId, _new_owner: AccountId) {}
 
@@ -51,12 +147,10 @@ pub enum FactoryError {
     FeeTooBig,
     TickSpacingOutOfBonds,
     NoTickSpacing,
+    PoolAlreadyExists,
     PoolInstantiationFailed,
-    OwnableError(OwnableError),
-}
-
-impl From<OwnableError> for FactoryError {
-    fn from(error: OwnableError) -> Self {
-        FactoryError::OwnableError(error)
-    }
+    CallerIsNotOwner,
+    CallerIsNotPendingOwner,
+    SetCodeHashFailed,
+    StorageUpToDate,
 }
@@ -1,2 +1,3 @@
+pub mod callback;
 pub mod factory;
 pub mod pool;
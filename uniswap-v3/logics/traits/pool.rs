@@ -1,5 +1,13 @@
-use crate::impls::pool::data_struct::*;
+use crate::{
+    helpers::{
+        full_math::FullMathError, liquidity_helper::LiquidityHelperError, oracle::OracleError,
+        position::PositionError, sqrt_price_math::SqrtPriceMathError, tick::TickHelperError,
+        tick_bitmap::TickBitmapError, tick_math::TickMathError,
+    },
+    impls::pool::data_struct::*,
+};
 use ink_prelude::vec::Vec;
+use primitive_types::U256;
 
 use openbrush::{
     contracts::{
@@ -14,13 +22,9 @@ pub type PoolRef = dyn Pool;
 
 #[openbrush::trait_definition]
 pub trait Pool {
+    /// Sets the initial price for the pool and opens it, can only be called once
     #[ink(message)]
-    fn initialize(
-        &mut self,
-        token_0: AccountId,
-        token_1: AccountId,
-        fee: u32,
-    ) -> Result<(), PoolError>;
+    fn initialize(&mut self, sqrt_price_x96: U256) -> Result<(), PoolError>;
 
     #[ink(message)]
     fn get_token_0(&self) -> AccountId;
@@ -38,23 +42,29 @@ pub trait Pool {
     fn get_max_liquidity_per_tick(&self) -> u128;
 
     #[ink(message)]
-    fn get_slot_0(&self) -> Slot;
+    fn get_slot_0(&self) -> Result<Slot, PoolError>;
 
     #[ink(message)]
-    fn get_fee_growth_global_0x128(&self) -> u128;
+    fn get_fee_growth_global_0x128(&self) -> Result<U256, PoolError>;
 
     #[ink(message)]
-    fn get_fee_growth_global_1x128(&self) -> u128;
+    fn get_fee_growth_global_1x128(&self) -> Result<U256, PoolError>;
 
     // #[ink(message)]
     // fn get_protocol_fees(&self) -> ProtocolFees;
 
     #[ink(message)]
-    fn get_liquidity(&self) -> u128;
+    fn get_liquidity(&self) -> Result<u128, PoolError>;
 
     #[ink(message)]
-    fn get_tick_bitmap(&self, entry: i32) -> Option<u128>;
+    fn get_tick(&self, tick: i32) -> Result<Option<TickInfo>, PoolError>;
 
+    #[ink(message)]
+    fn get_tick_bitmap(&self, word_pos: i16) -> Result<U256, PoolError>;
+
+    /// Collects tokens owed to a position of the caller, up to the requested amounts
+    ///
+    /// Does not recompute fees earned, which must be done either via mint or burn of any amount of liquidity.
     #[ink(message)]
     fn collect(
         &mut self,
@@ -65,14 +75,23 @@ pub trait Pool {
         amount1_requested: Balance,
     ) -> Result<(Balance, Balance), PoolError>;
 
+    /// Swap token_0 for token_1, or token_1 for token_0
+    ///
+    /// The caller receives a `SwapCallback::swap_callback` in which it must pay the input amount, the returned
+    /// deltas of the pool balances are negative for the amount sent to `recipient`.
     #[ink(message)]
     fn swap(
         &mut self,
         recipient: AccountId,
         zero_for_one: bool,
         amount_specified: i128,
-        sqrt_price_limit_x96: u128,
-    ) -> Result<(Balance, Balance), PoolError>;
+        sqrt_price_limit_x96: U256,
+        data: Vec<u8>,
+    ) -> Result<(i128, i128), PoolError>;
+
+    /// Receive token_0 and/or token_1 and pay it back, plus a fee, in the callback
+    ///
+    /// The caller receives a `FlashCallback::flash_callback` in which it must repay the amounts plus the fees.
     #[ink(message)]
     fn flash(
         &mut self,
@@ -82,35 +101,93 @@ pub trait Pool {
         data: Vec<u8>,
     ) -> Result<(), PoolError>;
 
+    /// Collect the protocol fee accrued to the pool, only callable by the factory owner
     #[ink(message)]
     fn collect_protocol(
         &mut self,
-        sender: AccountId,
         recipient: AccountId,
         amount0_requested: Balance,
         amount1_requested: Balance,
     ) -> Result<(Balance, Balance), PoolError>;
 
+    /// Set the denominator of the protocol's % share of the fees, only callable by the factory owner
+    ///
+    /// Each share is either 0 (off) or between 4 and 10, i.e. 1/4 to 1/10 of the swap fee of that token.
+    #[ink(message)]
+    fn set_fee_protocol(&mut self, fee_protocol0: u8, fee_protocol1: u8) -> Result<(), PoolError>;
+
+    /// Returns the tick and seconds per liquidity cumulatives as of each of `seconds_agos` before now,
+    /// fails with `OracleError::TargetTooOld` if a lookback is older than the oldest observation
+    ///
+    /// The seconds per liquidity cumulatives are U256 rather than u128: a single second at a liquidity of at
+    /// most 1 already adds 2^128, so a u128 would wrap between two readings. Only differences between two
+    /// readings are meaningful.
+    #[ink(message)]
+    fn observe(&self, seconds_agos: Vec<u32>) -> Result<(Vec<i64>, Vec<U256>), PoolError>;
+
+    /// Returns a snapshot of the tick cumulative, seconds per liquidity and seconds inside a tick range,
+    /// only comparable to other snapshots taken over a period for which a position existed
+    #[ink(message)]
+    fn snapshot_cumulatives_inside(
+        &self,
+        tick_lower: i32,
+        tick_upper: i32,
+    ) -> Result<(i64, U256, u32), PoolError>;
+
+    /// Increase the maximum number of price and liquidity observations that this pool will store
+    #[ink(message)]
+    fn increase_observation_cardinality_next(
+        &mut self,
+        observation_cardinality_next: u16,
+    ) -> Result<(), PoolError>;
+
     #[ink(message)]
     fn protocol_fees(&self) -> Result<(Balance, Balance), PoolError>;
-    // #[ink(message)]
+
+    /// Replaces the code of the pool, can only be called by the factory owner, see `Factory::migrate`
+    #[ink(message)]
+    fn set_code_hash(&mut self, code_hash: [u8; 32]) -> Result<(), PoolError>;
+
+    #[ink(message)]
+    fn storage_version(&self) -> u32;
+
+    /// Upgrades the pool storage, can only be called by the factory owner, see `Factory::migrate`
+    #[ink(message)]
+    fn migrate(&mut self) -> Result<(), PoolError>;
+
+    /// Migration hook, upgrades the storage from `from_version` to `STORAGE_VERSION`
+    fn _migrate(&mut self, from_version: u32) -> Result<(), PoolError>;
+
+    /// Adds liquidity for the given recipient/tick_lower/tick_upper position
+    ///
+    /// The caller receives a `MintCallback::mint_callback` in which it must pay any token owed for the
+    /// liquidity, `data` is passed through to the callback.
+    #[ink(message)]
     fn mint(
         &mut self,
         recipient: AccountId,
         tick_lower: i32,
         tick_upper: i32,
         amount: u128,
-        data: u128,
-    ) -> Result<(u128, u128), PoolError>;
+        data: Vec<u8>,
+    ) -> Result<(Balance, Balance), PoolError>;
 
-    #[ink(message)]
     fn _modify_position(
         &mut self,
         owner: AccountId,
         tick_lower: i32,
         tick_upper: i32,
-        liqudiity_delta: i128,
-    ) -> Result<(PositionInfo, Balance, Balance), PoolError>;
+        liquidity_delta: i128,
+    ) -> Result<(PositionInfo, i128, i128), PoolError>;
+
+    fn _update_position(
+        &mut self,
+        owner: AccountId,
+        tick_lower: i32,
+        tick_upper: i32,
+        liquidity_delta: i128,
+        tick: i32,
+    ) -> Result<PositionInfo, PoolError>;
 
     #[ink(message)]
     fn get_position(
@@ -118,11 +195,22 @@ pub trait Pool {
         owner: AccountId,
         tick_lower: i32,
         tick_upper: i32,
-    ) -> Option<PositionInfo>;
+    ) -> Result<Option<PositionInfo>, PoolError>;
 
     #[ink(message)]
     fn _check_ticks(&self, tick_lower: i32, tick_upper: i32) -> bool;
 
+    /// Fails with `NotInitialized` before `initialize` and with `PoolIsLocked` while a message holds the lock,
+    /// so the state views can't be read mid-operation
+    fn _require_unlocked(&self) -> Result<(), PoolError>;
+
+    fn _block_timestamp(&self) -> u32;
+
+    /// Burn liquidity from the caller and account tokens owed for the liquidity to the position
+    ///
+    /// Can be used to trigger a recalculation of fees owed to a position by calling with an amount of 0.
+    /// Fees must be collected separately via a call to `collect`.
+    #[ink(message)]
     fn burn(
         &mut self,
         tick_lower: i32,
@@ -130,9 +218,6 @@ pub trait Pool {
         amount: u128,
     ) -> Result<(Balance, Balance), PoolError>;
 
-    // #[ink(message)]
-    // fn _update_position(&self, onwer: AccountId, tick_lower: i32, tick_upper: i32, liquidity_delta: i128, tick: i32) -> Result<Balance, PoolError>;
-
     fn _emit_burn_event(
         &self,
         _owner: AccountId,
@@ -159,6 +244,7 @@ pub trait Pool {
 pub enum PoolError {
     ZeroAmmount,
     TickError,
+    TickNotInitialized,
     AddOverflowBalance0,
     AddOverflowBalance1,
     M0,
@@ -169,5 +255,79 @@ pub enum PoolError {
     CheckedNeg1,
     AmountSpecifiedIsZero,
     PoolIsLocked,
+    AlreadyInitialized,
+    CallerIsNotFactoryOwner,
+    InvalidFeeProtocol,
     SqrtPriceLimitX96IsInvalid,
+    InsufficientInputAmount,
+    ZeroLiquidity,
+    F0,
+    F1,
+    CastOverflow,
+    SetCodeHashFailed,
+    StorageUpToDate,
+    NotInitialized,
+    TickMathError(TickMathError),
+    FullMathError(FullMathError),
+    SqrtPriceMathError(SqrtPriceMathError),
+    TickBitmapError(TickBitmapError),
+    TickHelperError(TickHelperError),
+    LiquidityHelperError(LiquidityHelperError),
+    OracleError(OracleError),
+    PositionError(PositionError),
+    PSP22Error(PSP22Error),
+}
+
+impl From<TickMathError> for PoolError {
+    fn from(error: TickMathError) -> Self {
+        PoolError::TickMathError(error)
+    }
+}
+
+impl From<FullMathError> for PoolError {
+    fn from(error: FullMathError) -> Self {
+        PoolError::FullMathError(error)
+    }
+}
+
+impl From<SqrtPriceMathError> for PoolError {
+    fn from(error: SqrtPriceMathError) -> Self {
+        PoolError::SqrtPriceMathError(error)
+    }
+}
+
+impl From<TickBitmapError> for PoolError {
+    fn from(error: TickBitmapError) -> Self {
+        PoolError::TickBitmapError(error)
+    }
+}
+
+impl From<TickHelperError> for PoolError {
+    fn from(error: TickHelperError) -> Self {
+        PoolError::TickHelperError(error)
+    }
+}
+
+impl From<LiquidityHelperError> for PoolError {
+    fn from(error: LiquidityHelperError) -> Self {
+        PoolError::LiquidityHelperError(error)
+    }
+}
+
+impl From<OracleError> for PoolError {
+    fn from(error: OracleError) -> Self {
+        PoolError::OracleError(error)
+    }
+}
+
+impl From<PositionError> for PoolError {
+    fn from(error: PositionError) -> Self {
+        PoolError::PositionError(error)
+    }
+}
+
+impl From<PSP22Error> for PoolError {
+    fn from(error: PSP22Error) -> Self {
+        PoolError::PSP22Error(error)
+    }
 }